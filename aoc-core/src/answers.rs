@@ -0,0 +1,178 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::inputs::Variant;
+
+/// Known-good answers for one year, keyed like the input files they belong to.
+///
+/// ```toml
+/// [day16]
+/// part1 = "1701"
+///
+/// [day16_example]
+/// part1 = "1651"
+/// part2 = "1707"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, Parts>);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Parts {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Self> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Reads an answers file, a missing file simply has no answers.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(input) => {
+                Self::parse(&input).with_context(|| format!("invalid answers `{}`", path.display()))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("could not read `{}`", path.display())),
+        }
+    }
+
+    pub fn get(&self, day: u8, variant: Variant, part: u8) -> Option<&str> {
+        let parts = self.0.get(&key(day, variant))?;
+
+        match part {
+            1 => parts.part1.as_deref(),
+            2 => parts.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether any answer is recorded for this day and input variant.
+    pub fn contains(&self, day: u8, variant: Variant) -> bool {
+        self.0.contains_key(&key(day, variant))
+    }
+}
+
+fn key(day: u8, variant: Variant) -> String {
+    format!("day{}{}", day, variant.suffix())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing,
+}
+
+impl Outcome {
+    /// Compares an answer against the expected one. Surrounding whitespace is
+    /// ignored so multi-line answers can be written naturally in TOML.
+    pub fn check(expected: Option<&str>, actual: &str) -> Self {
+        match expected {
+            None => Outcome::Missing,
+            Some(expected) if expected.trim() == actual.trim() => Outcome::Pass,
+            Some(expected) => Outcome::Fail {
+                expected: expected.trim().to_string(),
+                actual: actual.trim().to_string(),
+            },
+        }
+    }
+}
+
+/// A line based diff, only the differing lines are marked.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected = expected.lines().collect::<Vec<&str>>();
+    let actual = actual.lines().collect::<Vec<&str>>();
+
+    if expected.len() <= 1 && actual.len() <= 1 {
+        return format!(
+            "- {}\n+ {}",
+            expected.first().unwrap_or(&""),
+            actual.first().unwrap_or(&"")
+        );
+    }
+
+    let mut out = vec![];
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => out.push(format!("  {}", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    out.push(format!("- {}", e));
+                }
+                if let Some(a) = a {
+                    out.push(format!("+ {}", a));
+                }
+            }
+        }
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inputs::Variant;
+
+    use super::{diff, Answers, Outcome};
+
+    const ANSWERS: &str = r#"
+[day1]
+part1 = "75501"
+part2 = "215594"
+
+[day10]
+part2 = """
+##..
+#..#
+"""
+
+[day16_example]
+part1 = "1651"
+"#;
+
+    #[test]
+    fn test_get() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+
+        assert_eq!(answers.get(1, Variant::Real, 1), Some("75501"));
+        assert_eq!(answers.get(1, Variant::Real, 2), Some("215594"));
+        assert_eq!(answers.get(10, Variant::Real, 1), None);
+        assert_eq!(answers.get(10, Variant::Real, 2), Some("##..\n#..#\n"));
+        assert_eq!(answers.get(16, Variant::Example, 1), Some("1651"));
+        assert_eq!(answers.get(16, Variant::Real, 1), None);
+        assert!(answers.contains(16, Variant::Example));
+        assert!(!answers.contains(1, Variant::Example));
+    }
+
+    #[test]
+    fn test_unknown_part() {
+        assert!(Answers::parse("[day1]\npart3 = \"1\"").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Outcome::check(Some("42"), "42"), Outcome::Pass);
+        assert_eq!(Outcome::check(Some("##\n"), "\n##"), Outcome::Pass);
+        assert_eq!(Outcome::check(None, "42"), Outcome::Missing);
+        assert_eq!(
+            Outcome::check(Some("42"), "41"),
+            Outcome::Fail {
+                expected: "42".to_string(),
+                actual: "41".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("42", "41"), "- 42\n+ 41");
+        assert_eq!(diff("##\n#.", "##\n.#"), "  ##\n- #.\n+ .#");
+        assert_eq!(diff("a\nb", "a"), "  a\n- b");
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Summary of the samples taken for one stage of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Result<Self> {
+        if samples.is_empty() {
+            bail!("at least one sample is needed");
+        }
+
+        samples.sort();
+
+        // Nearest rank, so a single sample is its own median and p95
+        let rank = |p: usize| samples[((samples.len() * p).div_ceil(100)).max(1) - 1];
+
+        Ok(Stats {
+            min: samples[0],
+            median: rank(50),
+            p95: rank(95),
+        })
+    }
+
+    /// Whether the median got slower than `baseline` by more than `threshold`
+    /// percent.
+    pub fn regressed_from(&self, baseline: &Stats, threshold: f64) -> bool {
+        self.median.as_secs_f64() > baseline.median.as_secs_f64() * (1.0 + threshold / 100.0)
+    }
+}
+
+/// Runs `f` `warmup` times untimed, then `iterations` timed times.
+pub fn measure<T>(
+    warmup: usize,
+    iterations: usize,
+    mut f: impl FnMut() -> Result<T>,
+) -> Result<Stats> {
+    for _ in 0..warmup {
+        std::hint::black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        std::hint::black_box(f()?);
+        samples.push(start.elapsed());
+    }
+
+    Stats::from_samples(samples)
+}
+
+/// Saved results, keyed by puzzle (`2022-16`, `2022-16_example`) and then by
+/// stage (`parse`, `part1`, `part2`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Baseline(BTreeMap<String, BTreeMap<String, Stats>>);
+
+impl Baseline {
+    /// Reads a baseline, a missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(input) => serde_json::from_str(&input)
+                .with_context(|| format!("invalid baseline `{}`", path.display())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("could not read `{}`", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("could not write `{}`", path.display()))
+    }
+
+    pub fn get(&self, puzzle: &str, stage: &str) -> Option<&Stats> {
+        self.0.get(puzzle)?.get(stage)
+    }
+
+    pub fn insert(&mut self, puzzle: &str, stage: &str, stats: Stats) {
+        self.0
+            .entry(puzzle.to_string())
+            .or_default()
+            .insert(stage.to_string(), stats);
+    }
+}
+
+mod nanos {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_nanos(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{measure, Baseline, Stats};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples((1..=20).rev().map(ms).collect()).unwrap();
+
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(10));
+        assert_eq!(stats.p95, ms(19));
+
+        let single = Stats::from_samples(vec![ms(3)]).unwrap();
+        assert_eq!(
+            (single.min, single.median, single.p95),
+            (ms(3), ms(3), ms(3))
+        );
+
+        assert!(Stats::from_samples(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_regressed_from() {
+        let stats = |median| Stats {
+            min: ms(1),
+            median: ms(median),
+            p95: ms(200),
+        };
+
+        assert!(stats(120).regressed_from(&stats(100), 10.0));
+        assert!(!stats(105).regressed_from(&stats(100), 10.0));
+        assert!(!stats(50).regressed_from(&stats(100), 10.0));
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        measure(2, 5, || {
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(calls, 7);
+        assert!(measure::<()>(0, 3, || anyhow::bail!("no")).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let stats = Stats {
+            min: ms(1),
+            median: ms(2),
+            p95: ms(3),
+        };
+
+        let mut baseline = Baseline::default();
+        baseline.insert("2022-16", "part1", stats);
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(
+            json,
+            r#"{"2022-16":{"part1":{"min":1000000,"median":2000000,"p95":3000000}}}"#
+        );
+
+        let baseline: Baseline = serde_json::from_str(&json).unwrap();
+        assert_eq!(baseline.get("2022-16", "part1"), Some(&stats));
+        assert_eq!(baseline.get("2022-16", "part2"), None);
+    }
+}
@@ -0,0 +1,655 @@
+use std::{
+    any::Any,
+    collections::{btree_map::Entry, BTreeMap},
+    env,
+    fs::{self, File},
+    io::{BufRead, BufWriter, Write},
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::{
+    answers::{diff, Answers, Outcome},
+    bench::{measure, Baseline, Stats},
+    inputs::{Variant, INPUT_DIR_ENV},
+    stream, Answer, PuzzleId, Registry,
+};
+
+#[derive(Parser)]
+#[command(about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one or more puzzles and print their answers
+    Run(RunArgs),
+    /// Check answers against each year's `answers.toml`
+    Verify(Selection),
+    /// Time parsing and both parts of one or more puzzles
+    Bench(BenchArgs),
+}
+
+#[derive(Args)]
+struct Selection {
+    /// Only use this year, every registered year when omitted
+    #[arg(long)]
+    year: Option<u16>,
+
+    /// A single day (`16`) or an inclusive range (`1-10`), every day when omitted
+    #[arg(long)]
+    day: Option<Days>,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Only run this part, both parts when omitted
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Read the puzzle input from this file, only valid for a single puzzle
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Use the `_example` input instead of the real one
+    #[arg(long)]
+    example: bool,
+
+    /// Stream the input instead of loading it whole, for puzzles that can
+    #[arg(long, value_enum)]
+    stream: Option<Streaming>,
+
+    /// Also draw what each part ends with into this directory, for puzzles that can
+    #[arg(long, conflicts_with = "stream")]
+    render: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Streaming {
+    /// Read the file through a buffer
+    Read,
+    /// Map the file into memory
+    Mmap,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Only time this part, both parts when omitted
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Use the `_example` input instead of the real one
+    #[arg(long)]
+    example: bool,
+
+    /// Timed runs of each stage
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    iterations: u32,
+
+    /// Untimed runs of each stage before timing starts
+    #[arg(long, default_value_t = 2)]
+    warmup: u32,
+
+    /// Compare medians against this JSON baseline
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Write the results into this JSON baseline, keeping other puzzles
+    #[arg(long)]
+    save: Option<PathBuf>,
+
+    /// Percentage a median may grow over the baseline before it is a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Days(RangeInclusive<u8>);
+
+impl FromStr for Days {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |day: &str| {
+            day.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("invalid day `{}`", day))
+        };
+
+        let days = match s.split_once('-') {
+            Some((from, to)) => parse(from)?..=parse(to)?,
+            None => parse(s)?..=parse(s)?,
+        };
+
+        if days.is_empty() {
+            return Err(format!("empty range of days `{}`", s));
+        }
+
+        Ok(Days(days))
+    }
+}
+
+/// Entry point shared by every binary in the workspace. Returns a failure
+/// exit code when any selected puzzle could not be solved.
+pub fn main(registry: Registry) -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Run(args) => run(&registry, &args),
+        Command::Verify(selection) => verify(&registry, &selection),
+        Command::Bench(args) => bench(&registry, &args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(registry: &Registry, args: &RunArgs) -> Result<()> {
+    let puzzles = select(registry, &args.selection)?;
+
+    if args.input.is_some() && puzzles.len() > 1 {
+        bail!("--input can only be used when a single puzzle is selected");
+    }
+
+    let variant = if args.example {
+        Variant::Example
+    } else {
+        Variant::Real
+    };
+    let env_dir = input_dir_from_env();
+
+    let mut failures = 0;
+    for puzzle in puzzles {
+        let path =
+            registry
+                .inputs()
+                .locate(puzzle, variant, args.input.as_deref(), env_dir.as_deref());
+
+        let result = path.and_then(|path| {
+            catch_panic(|| match args.stream {
+                Some(streaming) => stream_puzzle(registry, puzzle, args.part, &path, streaming),
+                None => run_puzzle(registry, puzzle, args.part, &path, args.render.as_deref()),
+            })
+        });
+
+        if let Err(err) = result {
+            eprintln!("{} Day {}: {:#}", puzzle.year, puzzle.day, err);
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} puzzle(s) failed", failures);
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Tally {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+}
+
+fn verify(registry: &Registry, selection: &Selection) -> Result<()> {
+    let puzzles = select(registry, selection)?;
+    let env_dir = input_dir_from_env();
+
+    let mut answers = BTreeMap::new();
+    let mut tally = Tally::default();
+
+    for puzzle in puzzles {
+        let answers = match answers.entry(puzzle.year) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(match registry.answers_file(puzzle.year) {
+                Some(path) => Answers::load(path)?,
+                None => Answers::default(),
+            }),
+        };
+
+        for variant in [Variant::Real, Variant::Example] {
+            if variant == Variant::Example && !answers.contains(puzzle.day, variant) {
+                continue;
+            }
+
+            verify_puzzle(
+                registry,
+                puzzle,
+                variant,
+                answers,
+                env_dir.as_deref(),
+                &mut tally,
+            );
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} missing",
+        tally.passed, tally.failed, tally.missing
+    );
+
+    if tally.failed > 0 {
+        bail!("{} answer(s) did not match", tally.failed);
+    }
+
+    Ok(())
+}
+
+/// Checks both parts of one puzzle. Parts without an expected answer are
+/// reported as missing and not run, so slow parts never block verification.
+fn verify_puzzle(
+    registry: &Registry,
+    puzzle: PuzzleId,
+    variant: Variant,
+    answers: &Answers,
+    env_dir: Option<&Path>,
+    tally: &mut Tally,
+) {
+    let label = |part: u8| match variant {
+        Variant::Real => format!("{} Day {}-{}", puzzle.year, puzzle.day, part),
+        Variant::Example => format!("{} Day {}-{} (example)", puzzle.year, puzzle.day, part),
+    };
+
+    let expected = [1, 2].map(|part| answers.get(puzzle.day, variant, part));
+
+    let solve = || -> Result<[Option<String>; 2]> {
+        if expected.iter().all(Option::is_none) {
+            return Ok([None, None]);
+        }
+
+        let solution = registry
+            .get(puzzle.year, puzzle.day)
+            .ok_or_else(|| anyhow!("no solution registered"))?;
+        let path = registry.inputs().locate(puzzle, variant, None, env_dir)?;
+        let input = fs::read_to_string(&path)
+            .with_context(|| format!("could not read input `{}`", path.display()))?;
+        let input = solution.parse(&input)?;
+
+        let part1 = match expected[0] {
+            Some(_) => Some(solution.part1(input.as_ref())?.to_string()),
+            None => None,
+        };
+        let part2 = match expected[1] {
+            Some(_) => Some(solution.part2(input.as_ref())?.to_string()),
+            None => None,
+        };
+
+        Ok([part1, part2])
+    };
+
+    let actual = match catch_panic(solve) {
+        Ok(actual) => actual,
+        Err(err) => {
+            println!("{} Day {}: error: {:#}", puzzle.year, puzzle.day, err);
+            tally.failed += expected.iter().flatten().count();
+            tally.missing += expected.iter().filter(|e| e.is_none()).count();
+            return;
+        }
+    };
+
+    for (part, (expected, actual)) in (1..).zip(expected.into_iter().zip(actual)) {
+        match Outcome::check(expected, actual.as_deref().unwrap_or_default()) {
+            Outcome::Pass => {
+                println!("{}: pass", label(part));
+                tally.passed += 1;
+            }
+            Outcome::Fail { expected, actual } => {
+                println!("{}: FAIL\n{}", label(part), diff(&expected, &actual));
+                tally.failed += 1;
+            }
+            Outcome::Missing => {
+                println!("{}: missing", label(part));
+                tally.missing += 1;
+            }
+        }
+    }
+}
+
+fn bench(registry: &Registry, args: &BenchArgs) -> Result<()> {
+    let puzzles = select(registry, &args.selection)?;
+    let variant = if args.example {
+        Variant::Example
+    } else {
+        Variant::Real
+    };
+    let env_dir = input_dir_from_env();
+
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+    let mut results = match &args.save {
+        Some(path) => Baseline::load(path)?,
+        None => Baseline::default(),
+    };
+
+    let mut failures = 0;
+    let mut regressions = 0;
+    for puzzle in puzzles {
+        let key = format!("{}-{}{}", puzzle.year, puzzle.day, variant.suffix());
+
+        let result =
+            catch_panic(|| bench_puzzle(registry, puzzle, variant, env_dir.as_deref(), args));
+        let stages = match result {
+            Ok(stages) => stages,
+            Err(err) => {
+                eprintln!("{} Day {}: {:#}", puzzle.year, puzzle.day, err);
+                failures += 1;
+                continue;
+            }
+        };
+
+        for (stage, stats) in stages {
+            let previous = baseline.as_ref().and_then(|b| b.get(&key, stage));
+            let regressed = previous.is_some_and(|b| stats.regressed_from(b, args.threshold));
+
+            print!(
+                "{} Day {:>2} {:<5}  min {:>10.2?}  median {:>10.2?}  p95 {:>10.2?}",
+                puzzle.year, puzzle.day, stage, stats.min, stats.median, stats.p95
+            );
+            match previous {
+                Some(previous) => println!(
+                    "  baseline {:>10.2?}{}",
+                    previous.median,
+                    if regressed { "  REGRESSION" } else { "" }
+                ),
+                None => println!(),
+            }
+
+            if regressed {
+                regressions += 1;
+            }
+            results.insert(&key, stage, stats);
+        }
+    }
+
+    if let Some(path) = &args.save {
+        results.save(path)?;
+    }
+
+    if failures > 0 {
+        bail!("{} puzzle(s) failed", failures);
+    }
+
+    if regressions > 0 {
+        bail!(
+            "{} stage(s) regressed by more than {}%",
+            regressions,
+            args.threshold
+        );
+    }
+
+    Ok(())
+}
+
+fn bench_puzzle(
+    registry: &Registry,
+    puzzle: PuzzleId,
+    variant: Variant,
+    env_dir: Option<&Path>,
+    args: &BenchArgs,
+) -> Result<Vec<(&'static str, Stats)>> {
+    let solution = registry
+        .get(puzzle.year, puzzle.day)
+        .ok_or_else(|| anyhow!("no solution registered"))?;
+    let path = registry.inputs().locate(puzzle, variant, None, env_dir)?;
+    let input = fs::read_to_string(&path)
+        .with_context(|| format!("could not read input `{}`", path.display()))?;
+
+    let (warmup, iterations) = (args.warmup as usize, args.iterations as usize);
+
+    let mut stages = vec![(
+        "parse",
+        measure(warmup, iterations, || solution.parse(&input))?,
+    )];
+
+    let parsed = solution.parse(&input)?;
+    if args.part.is_none_or(|part| part == 1) {
+        let stats = measure(warmup, iterations, || solution.part1(parsed.as_ref()))?;
+        stages.push(("part1", stats));
+    }
+    if args.part.is_none_or(|part| part == 2) {
+        let stats = measure(warmup, iterations, || solution.part2(parsed.as_ref()))?;
+        stages.push(("part2", stats));
+    }
+
+    Ok(stages)
+}
+
+/// Runs `f`, turning a panic into an error so a broken solution only fails
+/// its own puzzle instead of the whole run.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "no message".to_string());
+
+        Err(anyhow!("panicked: {}", message))
+    })
+}
+
+fn input_dir_from_env() -> Option<PathBuf> {
+    env::var_os(INPUT_DIR_ENV).map(PathBuf::from)
+}
+
+fn select(registry: &Registry, selection: &Selection) -> Result<Vec<PuzzleId>> {
+    let puzzles = registry
+        .puzzles()
+        .filter(|p| selection.year.is_none_or(|year| p.year == year))
+        .filter(|p| {
+            selection
+                .day
+                .as_ref()
+                .is_none_or(|days| days.0.contains(&p.day))
+        })
+        .collect::<Vec<PuzzleId>>();
+
+    if puzzles.is_empty() {
+        bail!("no registered puzzle matches the selection");
+    }
+
+    Ok(puzzles)
+}
+
+fn run_puzzle(
+    registry: &Registry,
+    puzzle: PuzzleId,
+    part: Option<u8>,
+    path: &Path,
+    render: Option<&Path>,
+) -> Result<()> {
+    let solution = registry
+        .get(puzzle.year, puzzle.day)
+        .ok_or_else(|| anyhow!("no solution registered"))?;
+
+    let input = fs::read_to_string(path)
+        .with_context(|| format!("could not read input `{}`", path.display()))?;
+
+    let input = solution.parse(&input)?;
+
+    if part.is_none_or(|part| part == 1) {
+        let answer = solution.part1(input.as_ref())?;
+        print_answer(puzzle, 1, &answer);
+
+        if let Some(dir) = render {
+            render_part(registry, puzzle, input.as_ref(), 1, dir)?;
+        }
+    }
+
+    if part.is_none_or(|part| part == 2) {
+        let answer = solution.part2(input.as_ref())?;
+        print_answer(puzzle, 2, &answer);
+
+        if let Some(dir) = render {
+            render_part(registry, puzzle, input.as_ref(), 2, dir)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws what `part` ends with into `dir`, for puzzles that can, and says
+/// where under the answer.
+fn render_part(
+    registry: &Registry,
+    puzzle: PuzzleId,
+    input: &dyn Any,
+    part: u8,
+    dir: &Path,
+) -> Result<()> {
+    let Some(solution) = registry.get_rendering(puzzle.year, puzzle.day) else {
+        return Ok(());
+    };
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("could not create directory `{}`", dir.display()))?;
+    let path = dir.join(format!(
+        "{}-day{}-part{}.pnm",
+        puzzle.year, puzzle.day, part
+    ));
+    let file = File::create(&path)
+        .with_context(|| format!("could not create image `{}`", path.display()))?;
+
+    let mut out = BufWriter::new(file);
+    solution.render(input, part, &mut out)?;
+    out.flush()
+        .with_context(|| format!("could not write image `{}`", path.display()))?;
+
+    println!("    drawn in {}", path.display());
+
+    Ok(())
+}
+
+/// Prints a part's answer, with its note indented below when there is one.
+fn print_answer(puzzle: PuzzleId, part: u8, answer: &Answer) {
+    println!("{} Day {}-{}: {}", puzzle.year, puzzle.day, part, answer);
+
+    if let Some(note) = answer.note() {
+        println!("    {}", note);
+    }
+}
+
+/// Like `run_puzzle`, but each part reads the input afresh as a stream.
+fn stream_puzzle(
+    registry: &Registry,
+    puzzle: PuzzleId,
+    part: Option<u8>,
+    path: &Path,
+    streaming: Streaming,
+) -> Result<()> {
+    let solution = registry
+        .get_streaming(puzzle.year, puzzle.day)
+        .ok_or_else(|| anyhow!("this puzzle cannot stream its input"))?;
+
+    let map = match streaming {
+        Streaming::Mmap => Some(stream::map(path)?),
+        Streaming::Read => None,
+    };
+    let open = || -> Result<Box<dyn BufRead + '_>> {
+        match &map {
+            Some(map) => Ok(Box::new(&map[..])),
+            None => Ok(Box::new(stream::open(path)?)),
+        }
+    };
+
+    if part.is_none_or(|part| part == 1) {
+        let answer = solution.part1(&mut open()?)?;
+        print_answer(puzzle, 1, &answer);
+    }
+
+    if part.is_none_or(|part| part == 2) {
+        let answer = solution.part2(&mut open()?)?;
+        print_answer(puzzle, 2, &answer);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{PuzzleId, Registry, Solution};
+
+    use super::{catch_panic, select, Days, Selection};
+
+    struct Noop;
+
+    impl Solution for Noop {
+        type Input = ();
+        type Part1 = u8;
+        type Part2 = u8;
+
+        fn parse(_input: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> Result<Self::Part1> {
+            Ok(1)
+        }
+
+        fn part2(_input: &Self::Input) -> Result<Self::Part2> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!("16".parse::<Days>(), Ok(Days(16..=16)));
+        assert_eq!("1-10".parse::<Days>(), Ok(Days(1..=10)));
+        assert!("10-1".parse::<Days>().is_err());
+        assert!("x".parse::<Days>().is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let mut registry = Registry::new();
+        registry.register::<Noop>(2015, 1);
+        registry.register::<Noop>(2022, 1);
+        registry.register::<Noop>(2022, 5);
+        registry.register::<Noop>(2022, 16);
+
+        let selection = |year, day| Selection { year, day };
+
+        assert_eq!(select(&registry, &selection(None, None)).unwrap().len(), 4);
+        assert_eq!(
+            select(&registry, &selection(Some(2022), Some(Days(1..=10)))).unwrap(),
+            vec![PuzzleId::new(2022, 1), PuzzleId::new(2022, 5)]
+        );
+        assert_eq!(
+            select(&registry, &selection(None, Some(Days(1..=1)))).unwrap(),
+            vec![PuzzleId::new(2015, 1), PuzzleId::new(2022, 1)]
+        );
+        assert!(select(&registry, &selection(Some(2016), None)).is_err());
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| Ok(1)).unwrap(), 1);
+
+        let err = catch_panic(|| -> Result<()> { panic!("day {} broke", 7) }).unwrap_err();
+        assert_eq!(err.to_string(), "panicked: day 7 broke");
+
+        let err = catch_panic(|| -> Result<()> { panic!("no arguments") }).unwrap_err();
+        assert_eq!(err.to_string(), "panicked: no arguments");
+    }
+}
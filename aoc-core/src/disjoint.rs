@@ -0,0 +1,250 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Union-find over arbitrary items, with path compression and union by rank.
+///
+/// Items are numbered as they are inserted and the forest is kept in plain
+/// vectors, so only the item lookup goes through a hash map.
+#[derive(Debug, Clone)]
+pub struct DisjointSet<T> {
+    index: HashMap<T, usize>,
+    items: Vec<T>,
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    components: usize,
+}
+
+impl<T> Default for DisjointSet<T> {
+    fn default() -> Self {
+        DisjointSet {
+            index: HashMap::new(),
+            items: Vec::new(),
+            parents: Vec::new(),
+            ranks: Vec::new(),
+            sizes: Vec::new(),
+            components: 0,
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> DisjointSet<T> {
+    pub fn new() -> Self {
+        DisjointSet::default()
+    }
+
+    /// Number of items, whichever component they are in.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.index.contains_key(item)
+    }
+
+    /// Every item, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+        self.items.iter()
+    }
+
+    /// Adds `item` in a component of its own, returning whether it was new.
+    pub fn insert(&mut self, item: T) -> bool {
+        self.id(item).1
+    }
+
+    fn id(&mut self, item: T) -> (usize, bool) {
+        match self.index.entry(item) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let id = self.items.len();
+                self.items.push(entry.key().clone());
+                entry.insert(id);
+
+                self.parents.push(id);
+                self.ranks.push(0);
+                self.sizes.push(1);
+                self.components += 1;
+
+                (id, true)
+            }
+        }
+    }
+
+    fn root(&mut self, id: usize) -> usize {
+        let mut root = id;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point the whole chain straight at the root
+        let mut node = id;
+        while self.parents[node] != root {
+            node = std::mem::replace(&mut self.parents[node], root);
+        }
+
+        root
+    }
+
+    /// Like `root`, but without compressing the path.
+    fn root_of(&self, mut id: usize) -> usize {
+        while self.parents[id] != id {
+            id = self.parents[id];
+        }
+
+        id
+    }
+
+    /// The representative of the component `item` is in, `None` if it was
+    /// never inserted.
+    pub fn find(&mut self, item: &T) -> Option<&T> {
+        let id = *self.index.get(item)?;
+        let root = self.root(id);
+
+        Some(&self.items[root])
+    }
+
+    /// Merges the components of `left` and `right`, inserting either one if
+    /// needed. Returns whether they were apart before.
+    pub fn union(&mut self, left: T, right: T) -> bool {
+        let (left, _) = self.id(left);
+        let (right, _) = self.id(right);
+
+        let mut left = self.root(left);
+        let mut right = self.root(right);
+
+        if left == right {
+            return false;
+        }
+
+        if self.ranks[left] < self.ranks[right] {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        self.parents[right] = left;
+        self.sizes[left] += self.sizes[right];
+        if self.ranks[left] == self.ranks[right] {
+            self.ranks[left] += 1;
+        }
+        self.components -= 1;
+
+        true
+    }
+
+    /// Whether both items are known and in the same component.
+    pub fn connected(&mut self, left: &T, right: &T) -> bool {
+        match (self.index.get(left), self.index.get(right)) {
+            (Some(&left), Some(&right)) => self.root(left) == self.root(right),
+            _ => false,
+        }
+    }
+
+    /// Number of items in the component of `item`.
+    pub fn size_of(&mut self, item: &T) -> Option<usize> {
+        let id = *self.index.get(item)?;
+        let root = self.root(id);
+
+        Some(self.sizes[root])
+    }
+
+    /// Number of separate components.
+    pub fn num_components(&self) -> usize {
+        self.components
+    }
+
+    /// Every component keyed by its representative, members in insertion order.
+    pub fn components(&self) -> HashMap<&T, Vec<&T>> {
+        let mut components: HashMap<&T, Vec<&T>> = HashMap::new();
+
+        for (id, item) in self.items.iter().enumerate() {
+            let root = &self.items[self.root_of(id)];
+            components.entry(root).or_default().push(item);
+        }
+
+        components
+    }
+}
+
+impl<T: Hash + Eq + Clone> FromIterator<T> for DisjointSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = DisjointSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Hash + Eq + Clone> Extend<T> for DisjointSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DisjointSet;
+
+    #[test]
+    fn test_union_and_find() {
+        let mut set: DisjointSet<char> = "abcde".chars().collect();
+
+        assert_eq!(set.len(), 5);
+        assert_eq!(set.num_components(), 5);
+
+        assert!(set.union('a', 'b'));
+        assert!(set.union('c', 'd'));
+        assert!(set.union('b', 'd'));
+        assert!(!set.union('a', 'c'));
+
+        assert_eq!(set.num_components(), 2);
+        assert!(set.connected(&'a', &'d'));
+        assert!(!set.connected(&'a', &'e'));
+        assert!(!set.connected(&'a', &'z'));
+        let root = set.find(&'b').copied();
+        assert_eq!(set.find(&'c').copied(), root);
+        assert_eq!(set.find(&'z'), None);
+
+        assert_eq!(set.size_of(&'d'), Some(4));
+        assert_eq!(set.size_of(&'e'), Some(1));
+    }
+
+    #[test]
+    fn test_incremental_insertion() {
+        let mut set = DisjointSet::new();
+
+        assert!(set.insert(1));
+        assert!(!set.insert(1));
+
+        // Union inserts whatever it has not seen yet
+        assert!(set.union(2, 3));
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.num_components(), 2);
+        assert!(set.contains(&3));
+    }
+
+    #[test]
+    fn test_components() {
+        let mut set: DisjointSet<u32> = (0..10).collect();
+
+        for n in 0..10 {
+            set.union(n, n % 3);
+        }
+
+        let components = set.components();
+        assert_eq!(components.len(), 3);
+
+        let mut sizes: Vec<_> = components.values().map(Vec::len).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 3, 4]);
+
+        for (root, members) in components {
+            assert!(members.iter().all(|m| *m % 3 == *root % 3));
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::parse::ParseError;
+
+/// What can go wrong while solving a puzzle, shared by every year.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Errors {
+    /// The input does not read as the puzzle describes.
+    #[error("Parsing Error: `{0}`")]
+    ParseError(ParseError),
+    /// The input parsed, but the puzzle cannot be solved from it.
+    #[error("Invalid State: `{0}`")]
+    InvalidState(String),
+    /// The input, or something it must contain, is not there at all.
+    #[error("Missing Input: `{0}`")]
+    MissingInput(String),
+}
+
+// Not `#[from]`, which would also make the parse error this one's source and
+// print its message twice in an error chain.
+impl From<ParseError> for Errors {
+    fn from(err: ParseError) -> Self {
+        Errors::ParseError(err)
+    }
+}
@@ -0,0 +1,443 @@
+//! Searches over graphs described by a neighbour closure, so puzzles never
+//! have to build an explicit graph. Every search accepts several sources.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::point::Num;
+
+/// A path from a source to a goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+/// Every node a search reached, with its cost and the node it came from.
+#[derive(Debug, Clone)]
+pub struct Reached<N, C> {
+    costs: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N, C> Default for Reached<N, C> {
+    fn default() -> Self {
+        Reached {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Reached<N, C> {
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> + '_ {
+        self.costs.iter().map(|(node, cost)| (node, *cost))
+    }
+
+    /// The path from the closest source to `node`.
+    pub fn path_to(&self, node: &N) -> Option<Path<N, C>> {
+        let cost = self.cost(node)?;
+
+        Some(Path {
+            cost,
+            nodes: rebuild(&self.parents, node.clone()),
+        })
+    }
+}
+
+fn rebuild<N: Eq + Hash + Clone>(parents: &HashMap<N, N>, goal: N) -> Vec<N> {
+    let mut nodes = vec![goal];
+
+    while let Some(parent) = parents.get(nodes.last().unwrap()) {
+        nodes.push(parent.clone());
+    }
+
+    nodes.reverse();
+    nodes
+}
+
+/// Breadth-first search visiting every node reachable from `sources`, where
+/// each step costs one.
+pub fn bfs<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+) -> Reached<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached = Reached::default();
+
+    bfs_inner(&mut reached, sources, neighbours, |_| false);
+
+    reached
+}
+
+/// Breadth-first search that stops at the first node matching `is_goal`.
+pub fn bfs_to<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached = Reached::default();
+
+    let goal = bfs_inner(&mut reached, sources, neighbours, is_goal)?;
+    reached.path_to(&goal)
+}
+
+fn bfs_inner<N, I>(
+    reached: &mut Reached<N, usize>,
+    sources: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut queue = VecDeque::new();
+
+    for source in sources {
+        if reached.costs.insert(source.clone(), 0).is_none() {
+            queue.push_back((source, 0));
+        }
+    }
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(node);
+        }
+
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = reached.costs.entry(next.clone()) {
+                entry.insert(cost + 1);
+                reached.parents.insert(next.clone(), node.clone());
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Heap entry ordered by priority only, smallest first.
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+/// Dijkstra's algorithm visiting every node reachable from `sources`.
+/// `neighbours` yields each next node with the cost of the edge to it.
+pub fn dijkstra<N, C, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+) -> Reached<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Num,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::default();
+
+    best_first(&mut reached, sources, neighbours, |_| C::ZERO, |_| false);
+
+    reached
+}
+
+/// Dijkstra's algorithm that stops at the first settled node matching `is_goal`.
+pub fn dijkstra_to<N, C, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Num,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(sources, neighbours, |_| C::ZERO, is_goal)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost, or the
+/// path found may not be the cheapest.
+pub fn astar<N, C, I>(
+    sources: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Num,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::default();
+
+    let goal = best_first(&mut reached, sources, neighbours, heuristic, is_goal)?;
+    reached.path_to(&goal)
+}
+
+fn best_first<N, C, I>(
+    reached: &mut Reached<N, C>,
+    sources: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<N>
+where
+    N: Eq + Hash + Clone,
+    C: Num,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut heap = BinaryHeap::new();
+
+    for source in sources {
+        reached.costs.insert(source.clone(), C::ZERO);
+        heap.push(Queued {
+            priority: heuristic(&source),
+            cost: C::ZERO,
+            node: source,
+        });
+    }
+
+    while let Some(Queued { cost, node, .. }) = heap.pop() {
+        // A cheaper way to this node was already settled
+        if reached.costs.get(&node).is_some_and(|best| *best < cost) {
+            continue;
+        }
+
+        if is_goal(&node) {
+            return Some(node);
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+
+            if reached
+                .costs
+                .get(&next)
+                .is_none_or(|best| next_cost < *best)
+            {
+                reached.costs.insert(next.clone(), next_cost);
+                reached.parents.insert(next.clone(), node.clone());
+                heap.push(Queued {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Shortest distances between every pair of the `size` nodes numbered
+/// `0..size`, `None` where there is no path. `edges` are directed.
+pub fn floyd_warshall<C: Num>(
+    size: usize,
+    edges: impl IntoIterator<Item = (usize, usize, C)>,
+) -> Vec<Vec<Option<C>>> {
+    let mut distances = vec![vec![None; size]; size];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[i] = Some(C::ZERO);
+    }
+
+    for (from, to, cost) in edges {
+        let current = &mut distances[from][to];
+        if current.is_none_or(|c| cost < c) {
+            *current = Some(cost);
+        }
+    }
+
+    for k in 0..size {
+        let through_k = distances[k].clone();
+
+        for row in distances.iter_mut() {
+            let Some(to_k) = row[k] else {
+                continue;
+            };
+
+            for (current, from_k) in row.iter_mut().zip(&through_k) {
+                if let Some(from_k) = from_k {
+                    let through = to_k + *from_k;
+                    if current.is_none_or(|d| through < d) {
+                        *current = Some(through);
+                    }
+                }
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{grid::Grid, point::Point};
+
+    use super::{astar, bfs, bfs_to, dijkstra, dijkstra_to, floyd_warshall};
+
+    const MAZE: &str = "\
+S..#....
+.#.#.##.
+.#...#..
+.####.#.
+......#E";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(MAZE, Ok).unwrap()
+    }
+
+    fn open(grid: &Grid<char>) -> impl FnMut(&Point<usize>) -> Vec<Point<usize>> + '_ {
+        |p| grid.neighbours4(*p).filter(|n| grid[*n] != '#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let start = grid.position(|c| *c == 'S').unwrap();
+        let end = grid.position(|c| *c == 'E').unwrap();
+
+        let reached = bfs([start], open(&grid));
+        assert_eq!(reached.cost(&end), Some(15));
+        assert_eq!(reached.cost(&start), Some(0));
+        assert_eq!(reached.len(), grid.cells().filter(|c| **c != '#').count());
+
+        let path = reached.path_to(&end).unwrap();
+        assert_eq!(path.nodes.len(), 16);
+        assert_eq!(path.nodes.first(), Some(&start));
+        assert_eq!(path.nodes.last(), Some(&end));
+        assert!(path.nodes.windows(2).all(|w| w[0].manhattan(&w[1]) == 1));
+    }
+
+    #[test]
+    fn test_bfs_to() {
+        let grid = maze();
+        let end = grid.position(|c| *c == 'E').unwrap();
+
+        let path = bfs_to([Point::new(0, 0)], open(&grid), |p| *p == end).unwrap();
+        assert_eq!(path.cost, 15);
+
+        // From both corners the right one wins
+        let path = bfs_to([Point::new(0, 0), Point::new(7, 0)], open(&grid), |p| {
+            *p == end
+        })
+        .unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes[0], Point::new(7, 0));
+
+        assert!(bfs_to([Point::new(0, 0)], open(&grid), |p| p.x > 7).is_none());
+    }
+
+    fn weighted(node: &u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 7), (2, 9), (5, 14)],
+            1 => vec![(0, 7), (2, 10), (3, 15)],
+            2 => vec![(0, 9), (1, 10), (3, 11), (5, 2)],
+            3 => vec![(1, 15), (2, 11), (4, 6)],
+            4 => vec![(3, 6), (5, 9)],
+            5 => vec![(0, 14), (2, 2), (4, 9)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let reached = dijkstra([0], weighted);
+
+        assert_eq!(reached.cost(&4), Some(20));
+        assert_eq!(reached.cost(&3), Some(20));
+        assert_eq!(reached.cost(&6), None);
+        assert_eq!(reached.path_to(&4).unwrap().nodes, vec![0, 2, 5, 4]);
+
+        let path = dijkstra_to([0], weighted, |n| *n == 4).unwrap();
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.nodes, vec![0, 2, 5, 4]);
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = maze();
+        let start = grid.position(|c| *c == 'S').unwrap();
+        let end = grid.position(|c| *c == 'E').unwrap();
+
+        let path = astar(
+            [start],
+            |p| {
+                grid.neighbours4(*p)
+                    .filter(|n| grid[*n] != '#')
+                    .map(|n| (n, 1_usize))
+                    .collect::<Vec<_>>()
+            },
+            |p| p.manhattan(&end),
+            |p| *p == end,
+        )
+        .unwrap();
+
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.nodes.len(), 16);
+    }
+
+    #[test]
+    fn test_floyd_warshall() {
+        let edges = (0..6_u8).flat_map(|from| {
+            weighted(&from)
+                .into_iter()
+                .map(move |(to, cost)| (from as usize, to as usize, cost))
+        });
+
+        let distances = floyd_warshall(7, edges);
+
+        assert_eq!(distances[0][4], Some(20));
+        assert_eq!(distances[4][0], Some(20));
+        assert_eq!(distances[1][5], Some(12));
+        assert_eq!(distances[3][3], Some(0));
+        assert_eq!(distances[0][6], None);
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::point::{Direction8, Num, Point};
+
+/// A dense, rectangular grid stored row by row. `Point::x` is the column and
+/// `Point::y` the row, with `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            bail!("row {} has {} cells, expected {}", y, rows[y].len(), width);
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one row per line, mapping every character to a cell.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| cell(c).with_context(|| format!("invalid cell at {},{}", x, y)))
+                    .collect::<Result<Vec<T>>>()
+            })
+            .collect::<Result<Vec<Vec<T>>>>()?;
+
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether `point` is inside the grid, for any integer type.
+    pub fn contains<N>(&self, point: Point<N>) -> bool
+    where
+        N: Num,
+        usize: TryFrom<N>,
+    {
+        self.index_of(point).is_some()
+    }
+
+    pub fn get<N>(&self, point: Point<N>) -> Option<&T>
+    where
+        N: Num,
+        usize: TryFrom<N>,
+    {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut<N>(&mut self, point: Point<N>) -> Option<&mut T>
+    where
+        N: Num,
+        usize: TryFrom<N>,
+    {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    fn index_of<N>(&self, point: Point<N>) -> Option<usize>
+    where
+        N: Num,
+        usize: TryFrom<N>,
+    {
+        let point = point.try_cast::<usize>()?;
+
+        (point.x < self.width && point.y < self.height).then(|| point.y * self.width + point.x)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        (0..self.height).map(move |y| &self[Point::new(x, y)])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Cells walked from `from` in `direction` until the edge, `from` excluded.
+    /// Diagonal directions give the grid's diagonals.
+    pub fn ray(
+        &self,
+        from: Point<usize>,
+        direction: impl Into<Direction8>,
+    ) -> impl Iterator<Item = (Point<usize>, &T)> + '_ {
+        let direction = direction.into();
+
+        std::iter::successors(Some(from), move |p| p.step(direction))
+            .skip(1)
+            .map_while(|p| self.get(p).map(|cell| (p, cell)))
+    }
+
+    /// Orthogonal neighbours of `point` inside the grid.
+    pub fn neighbours4(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        point.neighbours4().filter(|p| self.contains(*p))
+    }
+
+    /// Orthogonal and diagonal neighbours of `point` inside the grid.
+    pub fn neighbours8(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        point.neighbours8().filter(|p| self.contains(*p))
+    }
+
+    /// Every point, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point<usize>> + '_ {
+        (0..self.height).flat_map(|y| (0..self.width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> + '_ {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter()
+    }
+
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        self.cells.iter_mut()
+    }
+
+    /// The first point, row by row, whose cell matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Point<usize>> {
+        self.iter().find(|(_, c)| predicate(c)).map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |p| Point::new(p.y, p.x))
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |p| {
+            Point::new(p.y, self.height - 1 - p.x)
+        })
+    }
+
+    /// Rotates a quarter turn counter-clockwise.
+    pub fn rotate_left(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |p| {
+            Point::new(self.width - 1 - p.y, p.x)
+        })
+    }
+
+    /// A grid of the given size where each cell is copied from `source(point)`.
+    fn rebuild(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(Point<usize>) -> Point<usize>,
+    ) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(|p| self[source(p)].clone())
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Renders one line per row, turning every cell into a character.
+    pub fn display<F: Fn(&T) -> char>(&self, cell: F) -> GridDisplay<'_, T, F> {
+        GridDisplay { grid: self, cell }
+    }
+}
+
+impl<T> Index<Point<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point<usize>) -> &T {
+        assert!(
+            point.x < self.width && point.y < self.height,
+            "{:?} is outside a {}x{} grid",
+            point,
+            self.width,
+            self.height
+        );
+
+        &self.cells[point.y * self.width + point.x]
+    }
+}
+
+impl<T> IndexMut<Point<usize>> for Grid<T> {
+    fn index_mut(&mut self, point: Point<usize>) -> &mut T {
+        assert!(
+            point.x < self.width && point.y < self.height,
+            "{:?} is outside a {}x{} grid",
+            point,
+            self.width,
+            self.height
+        );
+
+        &mut self.cells[point.y * self.width + point.x]
+    }
+}
+
+pub struct GridDisplay<'a, T, F> {
+    grid: &'a Grid<T>,
+    cell: F,
+}
+
+impl<T, F: Fn(&T) -> char> Display for GridDisplay<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.cell)(cell))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use crate::point::{Direction, Direction8, Point};
+
+    use super::Grid;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| {
+            c.to_digit(10).ok_or_else(|| anyhow!("not a digit"))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456");
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.row(0), &[1, 2, 3]);
+
+        assert!(Grid::parse("12\n3", Ok).is_err());
+        assert!(Grid::parse("1x", |c| c.to_digit(10).ok_or_else(|| anyhow!("no"))).is_err());
+    }
+
+    #[test]
+    fn test_checked_access() {
+        let mut grid = digits("12\n34");
+
+        assert_eq!(grid.get(Point::new(1_isize, 1)), Some(&4));
+        assert_eq!(grid.get(Point::new(-1_isize, 0)), None);
+        assert_eq!(grid.get(Point::new(2_usize, 0)), None);
+        assert!(grid.contains(Point::new(0_i64, 1)));
+        assert!(!grid.contains(Point::new(0_i64, 2)));
+
+        *grid.get_mut(Point::new(0_usize, 0)).unwrap() = 9;
+        grid[Point::new(1, 0)] = 8;
+        assert_eq!(grid.row(0), &[9, 8]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unchecked_out_of_bounds() {
+        let grid = digits("12\n34");
+        let _ = grid[Point::new(2, 0)];
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5, 8]);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(
+            grid.ray(Point::new(0, 0), Direction8::DownRight)
+                .map(|(_, c)| *c)
+                .collect::<Vec<_>>(),
+            vec![5, 9]
+        );
+        assert_eq!(
+            grid.ray(Point::new(1, 2), Direction::Up)
+                .map(|(p, c)| (p, *c))
+                .collect::<Vec<_>>(),
+            vec![(Point::new(1, 1), 5), (Point::new(1, 0), 2)]
+        );
+        assert_eq!(grid.neighbours4(Point::new(0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.position(|c| *c == 6), Some(Point::new(2, 1)));
+        assert_eq!(grid.cells().sum::<u32>(), 45);
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = digits("123\n456");
+
+        assert_eq!(grid.transpose(), digits("14\n25\n36"));
+        assert_eq!(grid.rotate_right(), digits("41\n52\n63"));
+        assert_eq!(grid.rotate_left(), digits("36\n25\n14"));
+        assert_eq!(grid.rotate_right().rotate_left(), grid);
+        assert_eq!(grid.map(|c| c * 2).row(1), &[8, 10, 12]);
+    }
+
+    #[test]
+    fn test_display() {
+        let grid = Grid::parse("#.\n.#", |c| Ok(c == '#')).unwrap();
+
+        assert_eq!(
+            grid.display(|lit| if *lit { '#' } else { '.' }).to_string(),
+            "#.\n.#"
+        );
+        assert_eq!(Grid::new(3, 1, 0).display(|_| 'o').to_string(), "ooo");
+    }
+}
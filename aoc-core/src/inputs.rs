@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::{Errors, PuzzleId};
+
+/// Environment variable pointing at a directory of puzzle inputs. Files are
+/// looked up in `$AOC_INPUT_DIR/y<year>/` first, then in the directory itself.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    #[default]
+    Real,
+    Example,
+}
+
+impl Variant {
+    pub(crate) fn suffix(&self) -> &'static str {
+        match self {
+            Self::Real => "",
+            Self::Example => "_example",
+        }
+    }
+}
+
+/// Finds input files for a puzzle. The search order is an explicit path
+/// (the CLI flag), then the `AOC_INPUT_DIR` directory, then the `inputs/`
+/// directory that each year crate registers for itself.
+#[derive(Debug, Default)]
+pub struct InputLocator {
+    year_dirs: BTreeMap<u16, PathBuf>,
+}
+
+impl InputLocator {
+    pub fn add_year_dir(&mut self, year: u16, dir: impl Into<PathBuf>) {
+        self.year_dirs.insert(year, dir.into());
+    }
+
+    /// Every file name a day's input may use, covering both the `day7.txt`
+    /// and the `7.txt` conventions.
+    pub fn file_names(day: u8, variant: Variant) -> [String; 2] {
+        [
+            format!("day{}{}.txt", day, variant.suffix()),
+            format!("{}{}.txt", day, variant.suffix()),
+        ]
+    }
+
+    /// Candidate paths in the order they are tried.
+    pub fn candidates(
+        &self,
+        puzzle: PuzzleId,
+        variant: Variant,
+        env_dir: Option<&Path>,
+    ) -> Vec<PathBuf> {
+        let mut dirs = vec![];
+
+        if let Some(dir) = env_dir {
+            dirs.push(dir.join(format!("y{}", puzzle.year)));
+            dirs.push(dir.to_path_buf());
+        }
+
+        if let Some(dir) = self.year_dirs.get(&puzzle.year) {
+            dirs.push(dir.clone());
+        }
+
+        dirs.iter()
+            .flat_map(|dir| {
+                Self::file_names(puzzle.day, variant)
+                    .into_iter()
+                    .map(move |name| dir.join(name))
+            })
+            .collect()
+    }
+
+    pub fn locate(
+        &self,
+        puzzle: PuzzleId,
+        variant: Variant,
+        flag: Option<&Path>,
+        env_dir: Option<&Path>,
+    ) -> Result<PathBuf> {
+        if let Some(path) = flag {
+            return Ok(path.to_path_buf());
+        }
+
+        let candidates = self.candidates(puzzle, variant, env_dir);
+        if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+            return Ok(path.clone());
+        }
+
+        Err(Errors::MissingInput(format!(
+            "no input found for {} day {}, tried: {}",
+            puzzle.year,
+            puzzle.day,
+            candidates
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use crate::{Errors, PuzzleId};
+
+    use super::{InputLocator, Variant};
+
+    #[test]
+    fn test_candidate_order() {
+        let mut locator = InputLocator::default();
+        locator.add_year_dir(2015, "/crate/y2015/inputs");
+
+        let candidates = locator.candidates(
+            PuzzleId::new(2015, 7),
+            Variant::Example,
+            Some(Path::new("/env")),
+        );
+
+        assert_eq!(
+            candidates,
+            vec![
+                PathBuf::from("/env/y2015/day7_example.txt"),
+                PathBuf::from("/env/y2015/7_example.txt"),
+                PathBuf::from("/env/day7_example.txt"),
+                PathBuf::from("/env/7_example.txt"),
+                PathBuf::from("/crate/y2015/inputs/day7_example.txt"),
+                PathBuf::from("/crate/y2015/inputs/7_example.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locate() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("3.txt"), "^v").unwrap();
+
+        let mut locator = InputLocator::default();
+        locator.add_year_dir(2015, &dir);
+
+        let flag = Path::new("/somewhere/else.txt");
+        let puzzle = PuzzleId::new(2015, 3);
+
+        assert_eq!(
+            locator
+                .locate(puzzle, Variant::Real, Some(flag), None)
+                .unwrap(),
+            flag
+        );
+        assert_eq!(
+            locator.locate(puzzle, Variant::Real, None, None).unwrap(),
+            dir.join("3.txt")
+        );
+        assert!(locator
+            .locate(puzzle, Variant::Example, None, None)
+            .is_err());
+        let missing = locator
+            .locate(PuzzleId::new(2022, 3), Variant::Real, None, None)
+            .unwrap_err();
+        assert!(matches!(
+            missing.downcast_ref::<Errors>(),
+            Some(Errors::MissingInput(_))
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod disjoint;
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod ocr;
+pub mod parse;
+pub mod point;
+pub mod registry;
+pub mod solution;
+pub mod sparse;
+pub mod stream;
+
+pub use disjoint::DisjointSet;
+pub use error::Errors;
+pub use grid::Grid;
+pub use point::{Direction, Direction8, Point};
+pub use registry::{PuzzleId, Registry};
+pub use solution::{Answer, RenderingSolution, Solution, StreamingSolution};
+pub use sparse::SparseGrid;
@@ -0,0 +1,110 @@
+//! Reads the block capitals some puzzles draw instead of printing an answer.
+
+use crate::Grid;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+
+/// Letters are four columns wide with one blank column after each.
+const GLYPH_STRIDE: usize = GLYPH_WIDTH + 1;
+
+/// Every letter known to appear in the 4x6 font.
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The letters drawn by the lit cells of `image`, or `None` if it is not
+/// six rows of known letters.
+pub fn recognize(image: &Grid<bool>) -> Option<String> {
+    // The gap after the last letter may or may not be there
+    let letters = (image.width() + 1) / GLYPH_STRIDE;
+    let fits = matches!(image.width() % GLYPH_STRIDE, 0 | GLYPH_WIDTH);
+
+    if image.height() != GLYPH_HEIGHT || letters == 0 || !fits {
+        return None;
+    }
+
+    (0..letters)
+        .map(|i| {
+            let left = i * GLYPH_STRIDE;
+            let gap_is_blank = image
+                .rows()
+                .all(|row| row.get(left + GLYPH_WIDTH).is_none_or(|lit| !lit));
+
+            gap_is_blank.then(|| glyph(image, left)).flatten()
+        })
+        .collect()
+}
+
+/// The letter whose top left corner is at column `left`.
+fn glyph(image: &Grid<bool>, left: usize) -> Option<char> {
+    GLYPHS
+        .iter()
+        .find(|(_, rows)| {
+            image.rows().zip(rows).all(|(row, pattern)| {
+                row[left..left + GLYPH_WIDTH]
+                    .iter()
+                    .zip(pattern.bytes())
+                    .all(|(lit, cell)| *lit == (cell == b'#'))
+            })
+        })
+        .map(|(letter, _)| *letter)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    use super::recognize;
+
+    fn image(rows: &str) -> Grid<bool> {
+        Grid::parse(rows, |c| Ok(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn test_recognize() {
+        let letters = image(
+            "###..#..#.#....#..#...##..##..####..##..\n\
+             #..#.#..#.#....#..#....#.#..#....#.#..#.\n\
+             #..#.####.#....####....#.#......#..#..#.\n\
+             ###..#..#.#....#..#....#.#.##..#...####.\n\
+             #....#..#.#....#..#.#..#.#..#.#....#..#.\n\
+             #....#..#.####.#..#..##...###.####.#..#.",
+        );
+
+        assert_eq!(recognize(&letters).as_deref(), Some("PHLHJGZA"));
+    }
+
+    #[test]
+    fn test_unknown_shapes() {
+        // Trimmed after the last letter
+        let trimmed = image("####\n#...\n###.\n#...\n#...\n####");
+        assert_eq!(recognize(&trimmed).as_deref(), Some("E"));
+
+        let scribble = image("#.#.\n.#.#\n#.#.\n.#.#\n#.#.\n.#.#");
+        assert_eq!(recognize(&scribble), None);
+
+        let too_short = image("####\n#...\n###.\n#...\n####");
+        assert_eq!(recognize(&too_short), None);
+
+        // Letters must be apart
+        let touching = image("#####\n#....\n###..\n#....\n#....\n#####");
+        assert_eq!(recognize(&touching), None);
+    }
+}
@@ -0,0 +1,500 @@
+//! A small parsing toolkit for puzzle inputs.
+//!
+//! An [`Input`] walks through the text. Each primitive either consumes what
+//! it recognises or fails with the line and column it stopped at, leaving
+//! the input where it was. Parsers are plain functions or closures taking
+//! `&mut Input`, so they compose by calling each other.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::point::Point;
+
+/// What was expected where, with 1-based line and column numbers. Problems
+/// with a whole line, like a duplicate name, have no column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about the whole of `line`.
+    pub fn at_line(line: usize, message: impl Display) -> Self {
+        ParseError {
+            line,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The text still to parse, remembering where it sits in the whole input.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+    text: &'a str,
+    rest: &'a str,
+    first_line: usize,
+}
+
+impl<'a> Input<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Input::at_line(text, 1)
+    }
+
+    /// Input for text that starts at `line` of a larger input.
+    pub fn at_line(text: &'a str, line: usize) -> Self {
+        Input {
+            text,
+            rest: text,
+            first_line: line,
+        }
+    }
+
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Line and column of the next character.
+    pub fn position(&self) -> (usize, usize) {
+        let consumed = &self.text[..self.text.len() - self.rest.len()];
+        let line = self.first_line + consumed.matches('\n').count();
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+
+        (line, consumed[line_start..].chars().count() + 1)
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, message: impl Display) -> ParseError {
+        let (line, column) = self.position();
+
+        ParseError {
+            line,
+            column: Some(column),
+            message: message.to_string(),
+        }
+    }
+
+    fn expected(&self, what: impl Display) -> ParseError {
+        self.error(format!("expected {}", what))
+    }
+
+    fn advance(&mut self, len: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+
+    /// The literal `tag`.
+    pub fn tag(&mut self, tag: &str) -> Result<&'a str, ParseError> {
+        if self.rest.starts_with(tag) {
+            Ok(self.advance(tag.len()))
+        } else {
+            Err(self.expected(format!("`{}`", tag)))
+        }
+    }
+
+    /// The first of `tags` the input starts with. List longer tags before
+    /// their prefixes.
+    pub fn one_of<'t>(&mut self, tags: &[&'t str]) -> Result<&'t str, ParseError> {
+        match tags.iter().find(|tag| self.rest.starts_with(**tag)) {
+            Some(tag) => {
+                self.advance(tag.len());
+                Ok(tag)
+            }
+            None => {
+                let tags: Vec<String> = tags.iter().map(|t| format!("`{}`", t)).collect();
+                Err(self.expected(tags.join(" or ")))
+            }
+        }
+    }
+
+    /// Characters while `f` holds, possibly none.
+    pub fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let len = self.rest.find(|c| !f(c)).unwrap_or(self.rest.len());
+        self.advance(len)
+    }
+
+    /// At least one character while `f` holds, `expected` naming them in
+    /// the error.
+    pub fn take_while1(
+        &mut self,
+        expected: &str,
+        f: impl Fn(char) -> bool,
+    ) -> Result<&'a str, ParseError> {
+        let taken = self.take_while(f);
+
+        if taken.is_empty() {
+            Err(self.expected(expected))
+        } else {
+            Ok(taken)
+        }
+    }
+
+    /// A run of letters.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        self.take_while1("a word", |c| c.is_alphabetic())
+    }
+
+    /// Skips spaces and tabs, never line breaks.
+    pub fn spaces(&mut self) -> &'a str {
+        self.take_while(|c| c == ' ' || c == '\t')
+    }
+
+    /// Digits without a sign.
+    pub fn unsigned<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = *self;
+        let digits = self.take_while1("a number", |c| c.is_ascii_digit())?;
+
+        number(&start, digits)
+    }
+
+    /// Digits with an optional `-` or `+` in front.
+    pub fn signed<T>(&mut self) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = *self;
+        let sign = self.attempt(|input| input.one_of(&["-", "+"])).is_some();
+
+        if let Err(err) = self.take_while1("a number", |c| c.is_ascii_digit()) {
+            *self = start;
+            return Err(err);
+        }
+
+        let len = start.rest.len() - self.rest.len();
+        let digits = &start.rest[usize::from(sign && start.rest.starts_with('+'))..len];
+
+        number(&start, digits).inspect_err(|_| *self = start)
+    }
+
+    /// Two signed numbers split by `separator`, like `3,-4`.
+    pub fn point<T>(&mut self, separator: &str) -> Result<Point<T>, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.all_or_nothing(|input| {
+            let x = input.signed()?;
+            input.tag(separator)?;
+            let y = input.signed()?;
+
+            Ok(Point { x, y })
+        })
+    }
+
+    /// One or more `item`s split by `separator`.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.all_or_nothing(|input| {
+            let mut items = vec![item(input)?];
+
+            while input.attempt(|input| input.tag(separator)).is_some() {
+                items.push(item(input)?);
+            }
+
+            Ok(items)
+        })
+    }
+
+    /// A `key: value` line, ignoring indentation, with `value` parsing what
+    /// follows the colon and its spaces.
+    pub fn key_value<T>(
+        &mut self,
+        key: &str,
+        value: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        self.all_or_nothing(|input| {
+            input.spaces();
+            input.tag(key)?;
+            input.tag(":")?;
+            input.spaces();
+
+            input.line(value)
+        })
+    }
+
+    /// Runs `parser` and then expects the end of the line.
+    pub fn line<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        self.all_or_nothing(|input| {
+            let value = parser(input)?;
+            input.line_end()?;
+
+            Ok(value)
+        })
+    }
+
+    /// A line break, or the end of the input.
+    pub fn line_end(&mut self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        self.one_of(&["\r\n", "\n"])
+            .map(|_| ())
+            .map_err(|_| self.expected("the end of the line"))
+    }
+
+    /// Fails unless everything was parsed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.expected("the end of the input"))
+        }
+    }
+
+    /// Runs `parser`, going back to where it started if it fails, so another
+    /// parser can be tried instead.
+    pub fn attempt<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Option<T> {
+        self.all_or_nothing(parser).ok()
+    }
+
+    fn all_or_nothing<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let start = *self;
+        parser(self).inspect_err(|_| *self = start)
+    }
+}
+
+fn number<T>(start: &Input<'_>, digits: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    digits
+        .parse()
+        .map_err(|err| start.error(format!("invalid number `{}`: {}", digits, err)))
+}
+
+/// Parses the whole of `text`, failing if `parser` leaves anything behind.
+pub fn all<'a, T>(
+    text: &'a str,
+    parser: impl FnOnce(&mut Input<'a>) -> Result<T, ParseError>,
+) -> Result<T, ParseError> {
+    let mut input = Input::new(text);
+    let value = parser(&mut input)?;
+    input.end()?;
+
+    Ok(value)
+}
+
+/// Parses every line of `text` on its own. Errors point at the right line.
+pub fn lines<'a, T>(
+    text: &'a str,
+    mut parser: impl FnMut(&mut Input<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut input = Input::at_line(line, i + 1);
+            let value = parser(&mut input)?;
+            input.end()?;
+
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Parses each block of lines between blank lines, like the groups of a
+/// puzzle input. Errors point at the right line of the whole text.
+pub fn blocks<'a, T>(
+    text: &'a str,
+    mut parser: impl FnMut(&mut Input<'a>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    split_blocks(text)
+        .into_iter()
+        .map(|(line, block)| {
+            let mut input = Input::at_line(block, line);
+            let value = parser(&mut input)?;
+            input.line_end()?;
+            input.end()?;
+
+            Ok(value)
+        })
+        .collect()
+}
+
+/// Blocks of non-blank lines with the line each one starts at, without
+/// their final line break.
+fn split_blocks(text: &str) -> Vec<(usize, &str)> {
+    let mut blocks = vec![];
+    let mut start = None;
+    let mut offset = 0;
+
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let blank = line.trim().is_empty();
+
+        match (start, blank) {
+            (None, false) => start = Some((i + 1, offset)),
+            (Some((line, from)), true) => {
+                blocks.push((line, text[from..offset].trim_end_matches(['\r', '\n'])));
+                start = None;
+            }
+            _ => {}
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((line, from)) = start {
+        blocks.push((line, text[from..].trim_end_matches(['\r', '\n'])));
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::point::Point;
+
+    use super::{all, blocks, lines, Input, ParseError};
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(all("42", |i| i.unsigned::<u32>()), Ok(42));
+        assert_eq!(all("-42", |i| i.signed::<i64>()), Ok(-42));
+        assert_eq!(all("+7", |i| i.signed::<i8>()), Ok(7));
+
+        let err = all("300", |i| i.unsigned::<u8>()).unwrap_err();
+        assert_eq!((err.line, err.column), (1, Some(1)));
+        assert!(err.message.starts_with("invalid number `300`"));
+
+        assert!(all("-1", |i| i.unsigned::<u32>()).is_err());
+        assert!(all("-", |i| i.signed::<i32>()).is_err());
+        assert!(all("-5", |i| i.signed::<u32>()).is_err());
+    }
+
+    #[test]
+    fn test_failures_leave_input_untouched() {
+        let mut input = Input::new("-x");
+
+        assert!(input.signed::<i32>().is_err());
+        assert_eq!(input.rest(), "-x");
+
+        let mut input = Input::new("1,2,x");
+        assert!(input.separated(",", |i| i.unsigned::<u8>()).is_err());
+        assert_eq!(input.rest(), "1,2,x");
+    }
+
+    #[test]
+    fn test_tokens_and_lists() {
+        let parsed = all("move 3 from [1, 2, 3]", |i| {
+            i.tag("move ")?;
+            let count = i.unsigned::<usize>()?;
+            i.tag(" from [")?;
+            let from = i.separated(", ", |i| i.unsigned::<u8>())?;
+            i.tag("]")?;
+
+            Ok((count, from))
+        });
+        assert_eq!(parsed, Ok((3, vec![1, 2, 3])));
+
+        let mut input = Input::new("tunnels lead");
+        assert_eq!(input.one_of(&["tunnels", "tunnel"]), Ok("tunnels"));
+        assert_eq!(input.one_of(&["x", "y"]).unwrap_err().column, Some(8));
+        assert_eq!(input.spaces(), " ");
+        assert_eq!(input.word(), Ok("lead"));
+        assert!(input.end().is_ok());
+    }
+
+    #[test]
+    fn test_points() {
+        assert_eq!(all("3,-4", |i| i.point(",")), Ok(Point::new(3_i64, -4)));
+        assert_eq!(
+            all("x=3, y=4", |i| {
+                i.tag("x=")?;
+                let x = i.signed::<i32>()?;
+                i.tag(", y=")?;
+                Ok(Point::new(x, i.signed()?))
+            }),
+            Ok(Point::new(3, 4))
+        );
+        assert!(all("3;4", |i| i.point::<i32>(",")).is_err());
+    }
+
+    #[test]
+    fn test_key_value() {
+        let mut input = Input::new("  Starting items: 79, 98\n  Test: divisible by 23");
+
+        let items = input.key_value("Starting items", |i| i.separated(", ", |i| i.unsigned()));
+        assert_eq!(items, Ok(vec![79_u32, 98]));
+
+        let test = input.key_value("Test", |i| {
+            i.tag("divisible by ")?;
+            i.unsigned::<u32>()
+        });
+        assert_eq!(test, Ok(23));
+        assert!(input.end().is_ok());
+
+        let err = Input::new("Test: 1 2").key_value("Test", |i| i.unsigned::<u8>());
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "line 1, column 8: expected the end of the line"
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let parsed = lines("1\n2\n3\n", |i| i.unsigned::<u8>());
+        assert_eq!(parsed, Ok(vec![1, 2, 3]));
+
+        let err = lines("1\n2\nx3", |i| i.unsigned::<u8>()).unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                line: 3,
+                column: Some(1),
+                message: "expected a number".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = "a\nb\n\nc\n\n\nd\ne\n";
+
+        let parsed = blocks(text, |i| i.separated("\n", |i| i.word()));
+        assert_eq!(parsed, Ok(vec![vec!["a", "b"], vec!["c"], vec!["d", "e"]]));
+
+        let err = blocks("1\n2\n\n3\nx\n", |i| {
+            i.separated("\n", |i| i.unsigned::<u8>())
+        });
+        assert_eq!(err.unwrap_err().line, 5);
+    }
+}
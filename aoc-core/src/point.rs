@@ -0,0 +1,417 @@
+use std::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// Integer types a `Point` can be made of.
+pub trait Num:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Absolute difference, which never underflows for unsigned types.
+    fn distance(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A point on a 2D plane. `y` grows downwards, like rows in a grid, so
+/// `Direction::Up` is `y - 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Num> Point<T> {
+    pub const ORIGIN: Point<T> = Point {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+
+    pub fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Point<T>) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// The neighbouring point in `direction`, `None` when it does not fit in `T`.
+    pub fn step(&self, direction: impl Into<Direction8>) -> Option<Point<T>> {
+        let (dx, dy) = direction.into().delta();
+
+        Some(Point {
+            x: offset(self.x, dx)?,
+            y: offset(self.y, dy)?,
+        })
+    }
+
+    /// Up, right, down and left neighbours, skipping any that do not fit in `T`.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point<T>> {
+        Direction::ALL.into_iter().filter_map(move |d| self.step(d))
+    }
+
+    /// All eight surrounding points, clockwise from up, skipping any that do
+    /// not fit in `T`.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point<T>> {
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(d))
+    }
+
+    /// Converts each coordinate, `None` when one of them does not fit in `U`.
+    pub fn try_cast<U: Num + TryFrom<T>>(&self) -> Option<Point<U>> {
+        Some(Point {
+            x: U::try_from(self.x).ok()?,
+            y: U::try_from(self.y).ok()?,
+        })
+    }
+}
+
+fn offset<T: Num>(value: T, delta: i8) -> Option<T> {
+    match delta {
+        1 => value.checked_add(T::ONE),
+        -1 => value.checked_sub(T::ONE),
+        _ => Some(value),
+    }
+}
+
+impl<T: Num + Neg<Output = T>> Point<T> {
+    /// Rotates a quarter turn clockwise around the origin.
+    pub fn rotate_right(&self) -> Point<T> {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotates a quarter turn counter-clockwise around the origin.
+    pub fn rotate_left(&self) -> Point<T> {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Each coordinate reduced to -1, 0 or 1.
+    pub fn signum(&self) -> Point<T> {
+        let signum = |v: T| match v.cmp(&T::ZERO) {
+            std::cmp::Ordering::Less => -T::ONE,
+            std::cmp::Ordering::Equal => T::ZERO,
+            std::cmp::Ordering::Greater => T::ONE,
+        };
+
+        Point::new(signum(self.x), signum(self.y))
+    }
+
+    /// A single step towards `other`, diagonal when both axes differ.
+    pub fn direction_to(&self, other: &Point<T>) -> Point<T> {
+        (*other - *self).signum()
+    }
+}
+
+impl<T: Num> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Num> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Num> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Point<T>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: Num> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Point<T>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: Num> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, scalar: T) -> Point<T> {
+        Point::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl<T: Num + Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// The four orthogonal directions, clockwise from up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(&self) -> Direction {
+        Self::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Self::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn opposite(&self) -> Direction {
+        Self::ALL[(*self as usize + 2) % 4]
+    }
+
+    /// The unit vector of this direction.
+    pub fn offset<T: Num + Neg<Output = T>>(&self) -> Point<T> {
+        let (dx, dy) = Direction8::from(*self).delta();
+        let unit = |d: i8| match d {
+            1 => T::ONE,
+            -1 => -T::ONE,
+            _ => T::ZERO,
+        };
+
+        Point::new(unit(dx), unit(dy))
+    }
+}
+
+/// The four orthogonal and four diagonal directions, clockwise from up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    pub fn turn_right(&self) -> Direction8 {
+        Self::ALL[(*self as usize + 2) % 8]
+    }
+
+    pub fn turn_left(&self) -> Direction8 {
+        Self::ALL[(*self as usize + 6) % 8]
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        Self::ALL[(*self as usize + 4) % 8]
+    }
+
+    fn delta(&self) -> (i8, i8) {
+        match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Direction8::Up,
+            Direction::Right => Direction8::Right,
+            Direction::Down => Direction8::Down,
+            Direction::Left => Direction8::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction8, Point};
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, -2);
+        let b = Point::new(3, 4);
+
+        assert_eq!(a + b, Point::new(4, 2));
+        assert_eq!(a - b, Point::new(-2, -6));
+        assert_eq!(b * 2, Point::new(6, 8));
+        assert_eq!(-a, Point::new(-1, 2));
+
+        let mut c = a;
+        c += b;
+        c -= Point::new(1, 1);
+        assert_eq!(c, Point::new(3, 1));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(8_i64, 7);
+        let b = Point::new(2, 10);
+
+        assert_eq!(a.manhattan(&b), 9);
+        assert_eq!(a.chebyshev(&b), 6);
+
+        let a = Point::new(0_usize, 5);
+        let b = Point::new(3, 1);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(b.manhattan(&a), 7);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(5_i32, 5);
+
+        assert_eq!(
+            p.neighbours4().collect::<Vec<_>>(),
+            vec![
+                Point::new(5, 4),
+                Point::new(6, 5),
+                Point::new(5, 6),
+                Point::new(4, 5)
+            ]
+        );
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|n| n.chebyshev(&p) == 1));
+
+        let corner = Point::new(0_usize, 0);
+        assert_eq!(
+            corner.neighbours4().collect::<Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(corner.neighbours8().count(), 3);
+    }
+
+    #[test]
+    fn test_step() {
+        let p = Point::new(0_u8, 255);
+
+        assert_eq!(p.step(Direction::Up), Some(Point::new(0, 254)));
+        assert_eq!(p.step(Direction::Down), None);
+        assert_eq!(p.step(Direction8::UpRight), Some(Point::new(1, 254)));
+        assert_eq!(p.step(Direction8::UpLeft), None);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let up = Direction::Up.offset::<i32>();
+
+        assert_eq!(up.rotate_right(), Direction::Right.offset());
+        assert_eq!(up.rotate_left(), Direction::Left.offset());
+        assert_eq!(
+            Point::new(3, 1).rotate_right().rotate_left(),
+            Point::new(3, 1)
+        );
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction8::UpLeft.turn_right(), Direction8::UpRight);
+        assert_eq!(Direction8::Up.turn_left(), Direction8::Left);
+        assert_eq!(Direction8::DownLeft.opposite(), Direction8::UpRight);
+    }
+
+    #[test]
+    fn test_direction_to() {
+        let a = Point::new(2_isize, 2);
+
+        assert_eq!(a.direction_to(&Point::new(2, 9)), Point::new(0, 1));
+        assert_eq!(a.direction_to(&Point::new(-5, 0)), Point::new(-1, -1));
+        assert_eq!(a.direction_to(&a), Point::new(0, 0));
+    }
+
+    #[test]
+    fn test_try_cast() {
+        assert_eq!(
+            Point::new(3_isize, 4).try_cast::<usize>(),
+            Some(Point::new(3, 4))
+        );
+        assert_eq!(Point::new(-1_isize, 4).try_cast::<usize>(), None);
+    }
+}
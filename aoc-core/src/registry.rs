@@ -0,0 +1,303 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    io::{BufRead, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{inputs::InputLocator, Answer, RenderingSolution, Solution, StreamingSolution};
+
+/// Identifies a puzzle by its year and day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PuzzleId {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl PuzzleId {
+    pub fn new(year: u16, day: u8) -> Self {
+        PuzzleId { year, day }
+    }
+}
+
+/// Object safe view of a `Solution`, so days with different input and
+/// answer types can live in the same registry.
+pub trait DynSolution {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer>;
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer>;
+}
+
+struct Erased<S>(PhantomData<fn() -> S>);
+
+impl<S> DynSolution for Erased<S>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn part1(&self, input: &dyn Any) -> Result<Answer> {
+        Ok(S::part1(downcast::<S>(input)?)?.into())
+    }
+
+    fn part2(&self, input: &dyn Any) -> Result<Answer> {
+        Ok(S::part2(downcast::<S>(input)?)?.into())
+    }
+}
+
+/// Object safe view of a `StreamingSolution`.
+pub trait DynStreamingSolution {
+    fn part1(&self, input: &mut dyn BufRead) -> Result<Answer>;
+
+    fn part2(&self, input: &mut dyn BufRead) -> Result<Answer>;
+}
+
+impl<S: StreamingSolution> DynStreamingSolution for Erased<S> {
+    fn part1(&self, input: &mut dyn BufRead) -> Result<Answer> {
+        Ok(S::part1_stream(input)?.into())
+    }
+
+    fn part2(&self, input: &mut dyn BufRead) -> Result<Answer> {
+        Ok(S::part2_stream(input)?.into())
+    }
+}
+
+/// Object safe view of a `RenderingSolution`, drawing from the input its
+/// `DynSolution` parsed.
+pub trait DynRenderingSolution {
+    fn render(&self, input: &dyn Any, part: u8, out: &mut dyn Write) -> Result<()>;
+}
+
+impl<S> DynRenderingSolution for Erased<S>
+where
+    S: RenderingSolution,
+    S::Input: 'static,
+{
+    fn render(&self, input: &dyn Any, part: u8, out: &mut dyn Write) -> Result<()> {
+        S::render(downcast::<S>(input)?, part, out)
+    }
+}
+
+fn downcast<S>(input: &dyn Any) -> Result<&S::Input>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    input
+        .downcast_ref::<S::Input>()
+        .ok_or_else(|| anyhow!("input was parsed by a different solution"))
+}
+
+/// Every known solution, keyed by year and day.
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<PuzzleId, Box<dyn DynSolution>>,
+    streaming: BTreeMap<PuzzleId, Box<dyn DynStreamingSolution>>,
+    rendering: BTreeMap<PuzzleId, Box<dyn DynRenderingSolution>>,
+    inputs: InputLocator,
+    answer_files: BTreeMap<u16, PathBuf>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn register<S>(&mut self, year: u16, day: u8)
+    where
+        S: Solution + 'static,
+        S::Input: 'static,
+    {
+        self.solutions
+            .insert(PuzzleId::new(year, day), Box::new(Erased::<S>(PhantomData)));
+    }
+
+    /// Registers a solution that can also stream its input.
+    pub fn register_streaming<S>(&mut self, year: u16, day: u8)
+    where
+        S: StreamingSolution + 'static,
+        S::Input: 'static,
+    {
+        self.register::<S>(year, day);
+        self.streaming
+            .insert(PuzzleId::new(year, day), Box::new(Erased::<S>(PhantomData)));
+    }
+
+    /// Registers a solution that can also draw what its parts end with.
+    pub fn register_rendering<S>(&mut self, year: u16, day: u8)
+    where
+        S: RenderingSolution + 'static,
+        S::Input: 'static,
+    {
+        self.register::<S>(year, day);
+        self.rendering
+            .insert(PuzzleId::new(year, day), Box::new(Erased::<S>(PhantomData)));
+    }
+
+    /// Registers the directory a year crate keeps its inputs in.
+    pub fn add_input_dir(&mut self, year: u16, dir: impl Into<PathBuf>) {
+        self.inputs.add_year_dir(year, dir);
+    }
+
+    pub fn inputs(&self) -> &InputLocator {
+        &self.inputs
+    }
+
+    /// Registers the `answers.toml` a year crate keeps its known-good answers in.
+    pub fn add_answers_file(&mut self, year: u16, path: impl Into<PathBuf>) {
+        self.answer_files.insert(year, path.into());
+    }
+
+    pub fn answers_file(&self, year: u16) -> Option<&Path> {
+        self.answer_files.get(&year).map(PathBuf::as_path)
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&dyn DynSolution> {
+        self.solutions
+            .get(&PuzzleId::new(year, day))
+            .map(|s| s.as_ref())
+    }
+
+    pub fn get_streaming(&self, year: u16, day: u8) -> Option<&dyn DynStreamingSolution> {
+        self.streaming
+            .get(&PuzzleId::new(year, day))
+            .map(|s| s.as_ref())
+    }
+
+    pub fn get_rendering(&self, year: u16, day: u8) -> Option<&dyn DynRenderingSolution> {
+        self.rendering
+            .get(&PuzzleId::new(year, day))
+            .map(|s| s.as_ref())
+    }
+
+    pub fn puzzles(&self) -> impl Iterator<Item = PuzzleId> + '_ {
+        self.solutions.keys().copied()
+    }
+
+    pub fn run(&self, year: u16, day: u8, input: &str) -> Result<(Answer, Answer)> {
+        let solution = self
+            .get(year, day)
+            .ok_or_else(|| anyhow!("no solution registered for {} day {}", year, day))?;
+
+        let input = solution.parse(input)?;
+        let part1 = solution.part1(input.as_ref())?;
+        let part2 = solution.part2(input.as_ref())?;
+
+        Ok((part1, part2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{Answer, RenderingSolution, Solution, StreamingSolution};
+
+    use super::Registry;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u64>;
+        type Part1 = u64;
+        type Part2 = String;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input
+                .split(',')
+                .map(|n| n.parse())
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Self::Part1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Self::Part2> {
+            Ok(format!("{} numbers", input.len()))
+        }
+    }
+
+    #[test]
+    fn test_run_registered_solution() -> Result<()> {
+        let mut registry = Registry::new();
+        registry.register::<Sum>(2022, 1);
+
+        let (part1, part2) = registry.run(2022, 1, "1,2,3")?;
+
+        assert_eq!(part1, Answer::Integer(6));
+        assert_eq!(part2.to_string(), "3 numbers");
+        Ok(())
+    }
+
+    impl StreamingSolution for Sum {
+        fn part1_stream(input: &mut dyn std::io::BufRead) -> Result<Self::Part1> {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+
+            Sum::part1(&Sum::parse(&text)?)
+        }
+
+        fn part2_stream(_input: &mut dyn std::io::BufRead) -> Result<Self::Part2> {
+            Ok("streamed".to_string())
+        }
+    }
+
+    #[test]
+    fn test_streaming_solution() -> Result<()> {
+        let mut registry = Registry::new();
+        registry.register::<Sum>(2022, 1);
+        registry.register_streaming::<Sum>(2022, 2);
+
+        assert!(registry.get_streaming(2022, 1).is_none());
+        assert!(registry.get(2022, 2).is_some());
+
+        let solution = registry.get_streaming(2022, 2).unwrap();
+        assert_eq!(solution.part1(&mut "1,2,3".as_bytes())?, Answer::Integer(6));
+        assert_eq!(solution.part2(&mut "".as_bytes())?.to_string(), "streamed");
+        Ok(())
+    }
+
+    impl RenderingSolution for Sum {
+        fn render(input: &Self::Input, part: u8, out: &mut dyn std::io::Write) -> Result<()> {
+            Ok(write!(out, "P2\n{} 1\n{}\n{:?}", input.len(), part, input)?)
+        }
+    }
+
+    #[test]
+    fn test_rendering_solution() -> Result<()> {
+        let mut registry = Registry::new();
+        registry.register::<Sum>(2022, 1);
+        registry.register_rendering::<Sum>(2022, 2);
+
+        assert!(registry.get_rendering(2022, 1).is_none());
+        assert!(registry.get(2022, 2).is_some());
+
+        let input = registry.get(2022, 2).unwrap().parse("1,2")?;
+        let mut out = vec![];
+        registry
+            .get_rendering(2022, 2)
+            .unwrap()
+            .render(input.as_ref(), 2, &mut out)?;
+        assert_eq!(out, b"P2\n2 1\n2\n[1, 2]");
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_solution() {
+        let mut registry = Registry::new();
+        registry.register::<Sum>(2022, 1);
+
+        assert!(registry.get(2015, 1).is_none());
+        assert!(registry.run(2022, 2, "1").is_err());
+        assert_eq!(registry.puzzles().count(), 1);
+    }
+}
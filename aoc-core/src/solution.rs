@@ -0,0 +1,156 @@
+use std::{
+    fmt::{self, Display},
+    io::{BufRead, Write},
+};
+
+use anyhow::Result;
+
+use crate::{ocr, Grid};
+
+/// A single day's puzzle. The raw input is parsed once and then shared by
+/// both parts, which return their answers instead of printing them.
+pub trait Solution {
+    type Input;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2>;
+}
+
+/// A solution whose parts can also read the raw input as a stream, for
+/// inputs too big to load whole. Each part gets the input from the start.
+pub trait StreamingSolution: Solution {
+    fn part1_stream(input: &mut dyn BufRead) -> Result<Self::Part1>;
+
+    fn part2_stream(input: &mut dyn BufRead) -> Result<Self::Part2>;
+}
+
+/// A solution that can also draw what a part ends with, as a Netpbm image
+/// to look at rather than an answer to check.
+pub trait RenderingSolution: Solution {
+    fn render(input: &Self::Input, part: u8, out: &mut dyn Write) -> Result<()>;
+}
+
+/// What a part returns, formatted the same way whichever day it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// Letters drawn with lit cells, shown as the text they spell when the
+    /// font is recognised. `{:#}` always draws the picture.
+    Image(Grid<bool>),
+    /// An answer with more of what the solution found. The runner prints
+    /// `note` under the answer, `verify` only compares the answer.
+    Annotated(Box<Answer>, String),
+}
+
+impl Answer {
+    pub fn with_note(self, note: impl Into<String>) -> Self {
+        Answer::Annotated(Box::new(self), note.into())
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        match self {
+            Answer::Annotated(_, note) => Some(note),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Image(image) => match ocr::recognize(image) {
+                Some(letters) if !f.alternate() => write!(f, "{}", letters),
+                _ => write!(f, "{}", image.display(|lit| if *lit { '#' } else { '.' })),
+            },
+            Answer::Annotated(answer, _) => answer.fmt(f),
+        }
+    }
+}
+
+macro_rules! integer_answers {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(n.into())
+                }
+            }
+        )*
+    };
+}
+
+integer_answers!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(n as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Answer::Integer(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl From<Grid<bool>> for Answer {
+    fn from(image: Grid<bool>) -> Self {
+        Answer::Image(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Grid;
+
+    use super::Answer;
+
+    #[test]
+    fn test_format_answers() {
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!(Answer::from(-7_isize).to_string(), "-7");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+    }
+
+    #[test]
+    fn test_format_images() {
+        let letter = Grid::parse("#..#\n#..#\n####\n#..#\n#..#\n#..#", |c| Ok(c == '#')).unwrap();
+        assert_eq!(Answer::from(letter.clone()).to_string(), "H");
+        assert_eq!(
+            format!("{:#}", Answer::from(letter)),
+            "#..#\n#..#\n####\n#..#\n#..#\n#..#"
+        );
+
+        let blob = Grid::parse("##\n##", |c| Ok(c == '#')).unwrap();
+        assert_eq!(Answer::from(blob).to_string(), "##\n##");
+    }
+
+    #[test]
+    fn test_notes() {
+        let answer = Answer::from(42_u8).with_note("found quickly");
+        assert_eq!(answer.to_string(), "42");
+        assert_eq!(answer.note(), Some("found quickly"));
+        assert_eq!(Answer::from(42_u8).note(), None);
+    }
+}
@@ -0,0 +1,235 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::{self, Display},
+};
+
+use crate::point::Point;
+
+/// A grid over the whole plane that only stores occupied cells, for puzzles
+/// whose area is unknown up front or keeps growing.
+///
+/// Cells live in a hash map for cheap lookups, while ordered row and column
+/// indexes keep the bounding box and row/column queries cheap too.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<isize>, T>,
+    rows: BTreeMap<isize, BTreeSet<isize>>,
+    columns: BTreeMap<isize, BTreeSet<isize>>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            rows: BTreeMap::new(),
+            columns: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Stores `value` at `point`, returning the value it replaced.
+    pub fn insert(&mut self, point: Point<isize>, value: T) -> Option<T> {
+        let previous = self.cells.insert(point, value);
+
+        if previous.is_none() {
+            self.rows.entry(point.y).or_default().insert(point.x);
+            self.columns.entry(point.x).or_default().insert(point.y);
+        }
+
+        previous
+    }
+
+    pub fn remove(&mut self, point: Point<isize>) -> Option<T> {
+        let removed = self.cells.remove(&point)?;
+
+        unindex(&mut self.rows, point.y, point.x);
+        unindex(&mut self.columns, point.x, point.y);
+
+        Some(removed)
+    }
+
+    pub fn get(&self, point: Point<isize>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point<isize>) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    pub fn contains(&self, point: Point<isize>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// Smallest and largest corner of the occupied region, both inclusive.
+    pub fn bounds(&self) -> Option<(Point<isize>, Point<isize>)> {
+        let (min_y, max_y) = (self.rows.keys().next()?, self.rows.keys().next_back()?);
+        let (min_x, max_x) = (
+            self.columns.keys().next()?,
+            self.columns.keys().next_back()?,
+        );
+
+        Some((Point::new(*min_x, *min_y), Point::new(*max_x, *max_y)))
+    }
+
+    /// Occupied cells of row `y`, from left to right.
+    pub fn row(&self, y: isize) -> impl Iterator<Item = (Point<isize>, &T)> + '_ {
+        self.rows
+            .get(&y)
+            .into_iter()
+            .flatten()
+            .map(move |x| self.entry(Point::new(*x, y)))
+    }
+
+    /// Occupied cells of column `x`, from top to bottom.
+    pub fn column(&self, x: isize) -> impl Iterator<Item = (Point<isize>, &T)> + '_ {
+        self.columns
+            .get(&x)
+            .into_iter()
+            .flatten()
+            .map(move |y| self.entry(Point::new(x, *y)))
+    }
+
+    /// Every occupied cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<isize>, &T)> + '_ {
+        self.rows.keys().flat_map(|y| self.row(*y))
+    }
+
+    fn entry(&self, point: Point<isize>) -> (Point<isize>, &T) {
+        (point, &self.cells[&point])
+    }
+
+    /// Renders the bounding box of the occupied cells, one line per row from
+    /// the smallest `y` down. Empty cells are passed as `None`.
+    pub fn display<F: Fn(Option<&T>) -> char>(&self, cell: F) -> SparseGridDisplay<'_, T, F> {
+        SparseGridDisplay { grid: self, cell }
+    }
+}
+
+fn unindex(index: &mut BTreeMap<isize, BTreeSet<isize>>, key: isize, value: isize) {
+    if let Some(set) = index.get_mut(&key) {
+        set.remove(&value);
+
+        if set.is_empty() {
+            index.remove(&key);
+        }
+    }
+}
+
+impl<T> FromIterator<(Point<isize>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point<isize>, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point<isize>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point<isize>, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+pub struct SparseGridDisplay<'a, T, F> {
+    grid: &'a SparseGrid<T>,
+    cell: F,
+}
+
+impl<T, F: Fn(Option<&T>) -> char> Display for SparseGridDisplay<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((min, max)) = self.grid.bounds() else {
+            return Ok(());
+        };
+
+        for y in min.y..=max.y {
+            if y > min.y {
+                writeln!(f)?;
+            }
+            for x in min.x..=max.x {
+                write!(f, "{}", (self.cell)(self.grid.get(Point::new(x, y))))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::point::Point;
+
+    use super::SparseGrid;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut grid = SparseGrid::new();
+
+        assert_eq!(grid.insert(Point::new(-3, 2), 'a'), None);
+        assert_eq!(grid.insert(Point::new(-3, 2), 'b'), Some('a'));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.get(Point::new(-3, 2)), Some(&'b'));
+
+        grid.insert(Point::new(5, -7), 'c');
+        assert_eq!(grid.bounds(), Some((Point::new(-3, -7), Point::new(5, 2))));
+
+        assert_eq!(grid.remove(Point::new(5, -7)), Some('c'));
+        assert_eq!(grid.remove(Point::new(5, -7)), None);
+        assert_eq!(grid.bounds(), Some((Point::new(-3, 2), Point::new(-3, 2))));
+
+        grid.remove(Point::new(-3, 2));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid: SparseGrid<u8> = [(3, 0), (-1, 0), (3, 4), (0, 9)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, y))| (Point::new(x, y), i as u8))
+            .collect();
+
+        assert_eq!(
+            grid.row(0).map(|(p, v)| (p.x, *v)).collect::<Vec<_>>(),
+            vec![(-1, 1), (3, 0)]
+        );
+        assert_eq!(
+            grid.column(3).map(|(p, v)| (p.y, *v)).collect::<Vec<_>>(),
+            vec![(0, 0), (4, 2)]
+        );
+        assert_eq!(grid.row(1).count(), 0);
+        assert_eq!(
+            grid.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![1, 0, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let grid: SparseGrid<()> = [(-1, -1), (1, 0)]
+            .into_iter()
+            .map(|(x, y)| (Point::new(x, y), ()))
+            .collect();
+
+        assert_eq!(
+            grid.display(|c| if c.is_some() { '#' } else { '.' })
+                .to_string(),
+            "#..\n..#"
+        );
+        assert_eq!(SparseGrid::<()>::new().display(|_| '#').to_string(), "");
+    }
+}
@@ -0,0 +1,193 @@
+//! Reads puzzle inputs a piece at a time, for inputs too big to load whole.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind},
+    iter,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use memmap2::Mmap;
+
+use crate::{
+    parse::{Input, ParseError},
+    Errors,
+};
+
+/// Opens `path` behind a read buffer.
+pub fn open(path: &Path) -> Result<BufReader<File>> {
+    let file =
+        File::open(path).with_context(|| format!("could not open input `{}`", path.display()))?;
+
+    Ok(BufReader::new(file))
+}
+
+/// Maps `path` into memory. Pages are only loaded when touched and can be
+/// dropped again, so reading the bytes front to back stays small. A byte
+/// slice is a `BufRead` too.
+pub fn map(path: &Path) -> Result<Mmap> {
+    let file =
+        File::open(path).with_context(|| format!("could not open input `{}`", path.display()))?;
+
+    // SAFETY: inputs are not expected to change while a puzzle runs. If one
+    // is truncated underneath us the process can crash, which is no worse
+    // than the half-read input a change would give anyway.
+    unsafe { Mmap::map(&file) }.with_context(|| format!("could not map input `{}`", path.display()))
+}
+
+/// Parses every line of `reader` on its own as it is read, like
+/// `parse::lines`. Errors point at the right line.
+pub fn lines<R, T, P>(reader: R, mut parser: P) -> impl Iterator<Item = Result<T>>
+where
+    R: BufRead,
+    P: FnMut(&mut Input) -> Result<T, ParseError>,
+{
+    reader.lines().enumerate().map(move |(i, line)| {
+        let line = line.with_context(|| format!("could not read line {}", i + 1))?;
+
+        let mut input = Input::at_line(&line, i + 1);
+        let value = parser(&mut input).and_then(|value| {
+            input.end()?;
+            Ok(value)
+        });
+
+        Ok(value.map_err(Errors::from)?)
+    })
+}
+
+/// Most bytes [`line_bytes`] copies out of its reader at once. A mapped
+/// file hands over all of itself as one buffer, which would otherwise be
+/// copied onto the heap whole.
+const CHUNK: usize = 64 * 1024;
+
+/// The bytes of the first line of `reader`, without its line break.
+pub fn line_bytes<R: BufRead>(mut reader: R) -> impl Iterator<Item = Result<u8>> {
+    // Buffers are copied out a chunk at a time, reading byte by byte through
+    // a `dyn BufRead` is several times slower
+    let mut chunk = Vec::with_capacity(CHUNK);
+    let mut next = 0;
+    let mut done = false;
+
+    iter::from_fn(move || loop {
+        if done {
+            return None;
+        }
+
+        if let Some(byte) = chunk.get(next) {
+            next += 1;
+            done = matches!(byte, b'\n' | b'\r');
+            return (!done).then_some(Ok(*byte));
+        }
+
+        chunk.clear();
+        next = 0;
+        match reader.fill_buf() {
+            Ok(buffer) => {
+                chunk.extend_from_slice(&buffer[..buffer.len().min(CHUNK)]);
+                reader.consume(chunk.len());
+                done = chunk.is_empty();
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => {
+                done = true;
+                return Some(Err(err).context("could not read input"));
+            }
+        }
+    })
+}
+
+/// Hands the values of `results` to `f` until the first error, which is
+/// returned instead of what `f` made of the values before it.
+pub fn process<T, U>(
+    results: impl Iterator<Item = Result<T>>,
+    f: impl FnOnce(&mut dyn Iterator<Item = T>) -> U,
+) -> Result<U> {
+    let mut error = None;
+    let mut values = results.map_while(|result| result.map_err(|err| error = Some(err)).ok());
+
+    let value = f(&mut values);
+    drop(values);
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor, Read};
+
+    use anyhow::Result;
+
+    use super::{line_bytes, lines, process, CHUNK};
+
+    /// A byte slice that remembers the most bytes consumed from it at once.
+    struct Watched<'a> {
+        bytes: &'a [u8],
+        most: &'a mut usize,
+    }
+
+    impl Read for Watched<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.bytes.read(buf)
+        }
+    }
+
+    impl BufRead for Watched<'_> {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            Ok(self.bytes)
+        }
+
+        fn consume(&mut self, amt: usize) {
+            *self.most = (*self.most).max(amt);
+            self.bytes.consume(amt);
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let numbers: Vec<u32> = lines(Cursor::new("1\n22\n333\n"), |i| i.unsigned())
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(numbers, vec![1, 22, 333]);
+
+        let err = lines("1\n2x\n".as_bytes(), |i| i.unsigned::<u32>())
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2, column 2: expected the end of the input`"
+        );
+    }
+
+    #[test]
+    fn test_process() {
+        let bytes: &[u8] = b"(()\nignored";
+        let count = process(line_bytes(bytes), |bytes| bytes.count()).unwrap();
+        assert_eq!(count, 3);
+
+        let results = vec![Ok(1), Ok(2), Err(anyhow::anyhow!("broken")), Ok(4)];
+        let mut seen = vec![];
+        let err = process(results.into_iter(), |values| seen.extend(values)).unwrap_err();
+        assert_eq!(err.to_string(), "broken");
+        assert_eq!(seen, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_line_bytes_chunks() {
+        let mut bytes = vec![b'('; 40 * CHUNK + 7];
+        bytes.extend_from_slice(b"\nignored");
+
+        let mut most = 0;
+        let reader = Watched {
+            bytes: &bytes,
+            most: &mut most,
+        };
+        let count = process(line_bytes(reader), |bytes| bytes.count()).unwrap();
+
+        assert_eq!(count, 40 * CHUNK + 7);
+        assert_eq!(most, CHUNK);
+    }
+}
@@ -0,0 +1,11 @@
+use std::process::ExitCode;
+
+use aoc_core::Registry;
+
+fn main() -> ExitCode {
+    let mut registry = Registry::new();
+    y2015::register(&mut registry);
+    y2022::register(&mut registry);
+
+    aoc_core::cli::main(registry)
+}
@@ -1,13 +1,55 @@
-use std::fs;
+use std::io::BufRead;
+use aoc_core::parse::{ self, ParseError };
+use aoc_core::{ stream, Errors, Solution, StreamingSolution };
 
-pub fn execute() -> anyhow::Result<()> {
-    println!("# Day 1");
-    let file = fs::read("./inputs/1.txt")?;
-    let file = &file[..];
-    println!("Part 1: {:?}", solve_part_1(file));
-    println!("Part 2: {:?}", solve_part_2(file));
+pub struct Day1;
 
-    Ok(())
+impl Solution for Day1 {
+    type Input = Vec<u8>;
+    type Part1 = i64;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let input = input.trim_end();
+        parse::all(input, |i| {
+            while !i.is_empty() {
+                i.one_of(&["(", ")"])?;
+            }
+            Ok(())
+        }).map_err(Errors::from)?;
+
+        Ok(input.as_bytes().to_vec())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        Ok(solve_part_2(input))
+    }
+}
+
+impl StreamingSolution for Day1 {
+    fn part1_stream(input: &mut dyn BufRead) -> anyhow::Result<Self::Part1> {
+        stream::process(directions(input), |directions| directions.map(|d| convert(&d)).sum())
+    }
+
+    fn part2_stream(input: &mut dyn BufRead) -> anyhow::Result<Self::Part2> {
+        stream::process(directions(input), |directions| basement(directions))
+    }
+}
+
+/// The directions on the first line of `input`, checked as they are read.
+fn directions(input: &mut dyn BufRead) -> impl Iterator<Item = anyhow::Result<u8>> + '_ {
+    stream::line_bytes(input).enumerate().map(|(idx, byte)| match byte? {
+        byte @ (b'(' | b')') => Ok(byte),
+        _ => {
+            let message = "expected `(` or `)`".to_string();
+            let err = ParseError { line: 1, column: Some(idx + 1), message };
+            Err(Errors::from(err).into())
+        }
+    })
 }
 
 fn solve_part_1(input: &[u8]) -> i64 {
@@ -23,9 +65,15 @@ fn convert(value: &u8) -> i64 {
 }
 
 fn solve_part_2(input: &[u8]) -> usize {
+    basement(input.iter().copied())
+}
+
+/// The position of the first direction that leads into the basement, or 0
+/// if none does. Stops reading there.
+fn basement(input: impl Iterator<Item = u8>) -> usize {
     let mut floor = 0;
-    for (idx, i) in input.iter().enumerate() {
-        let value = convert(i);
+    for (idx, i) in input.enumerate() {
+        let value = convert(&i);
         floor += value;
 
         if floor < 0 {
@@ -33,7 +81,7 @@ fn solve_part_2(input: &[u8]) -> usize {
         }
     }
 
-    return 0;
+    0
 }
 
 #[cfg(test)]
@@ -59,4 +107,13 @@ mod tests {
         assert_eq!(solve_part_2(")".as_bytes()), 1);
         assert_eq!(solve_part_2("()())".as_bytes()), 5);
     }
+
+    #[test]
+    fn streaming() {
+        assert_eq!(Day1::part1_stream(&mut "))(((((\n".as_bytes()).unwrap(), 3);
+        assert_eq!(Day1::part2_stream(&mut "()())(((x".as_bytes()).unwrap(), 5);
+
+        let err = Day1::part1_stream(&mut "(()x)".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "Parsing Error: `line 1, column 4: expected `(` or `)``");
+    }
 }
\ No newline at end of file
@@ -1,29 +1,48 @@
 use std::cmp::min;
-use std::fs;
 use std::str::FromStr;
-use anyhow::{ Result, anyhow };
+use anyhow::Result;
+use aoc_core::parse::{ self, Input, ParseError };
+use aoc_core::{ Errors, Solution };
 
-pub fn execute() -> Result<()> {
-    let file = fs::read_to_string("./inputs/2.txt")?;
-    let boxes: Vec<Rect> = file.split("\n")
-        .map(|i| Rect::from_str(i).unwrap())
-        .collect();
+pub struct Day2;
 
-    println!("# Day 2");
-    println!("Part 1: {:?}", solve_part_1(&boxes));
-    println!("Part 2: {:?}", solve_part_2(&boxes));
+impl Solution for Day2 {
+    type Input = Vec<Rect>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, Rect::parse).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(solve_part_2(input))
+    }
 }
 
 #[derive(Debug)]
-struct Rect {
+pub struct Rect {
     l: usize,
     w: usize,
     h: usize
 }
 
 impl Rect {
+    /// Dimensions like `2x3x4`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let l = input.unsigned()?;
+        input.tag("x")?;
+        let w = input.unsigned()?;
+        input.tag("x")?;
+        let h = input.unsigned()?;
+
+        Ok(Rect { l, w, h })
+    }
+
     fn surface_area(&self) -> usize {
         2 * self.l * self.w + 2 * self.w * self.h + 2 * self.h * self.l
     }
@@ -46,28 +65,20 @@ impl Rect {
 }
 
 impl FromStr for Rect {
-    type Err = anyhow::Error;
+    type Err = Errors;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lengths: Vec<usize> = s.split('x')
-            .map(|l| l.parse::<usize>().expect("invalid number"))
-            .collect();
-
-        if lengths.len() != 3 {
-            return Err(anyhow!("Expected {l}x{w}x{h}".to_string()));
-        }
-
-        Ok(Rect { l: lengths[0], w: lengths[1], h: lengths[2] })
+        Ok(parse::all(s, Rect::parse)?)
     }
 }
 
-fn solve_part_1(boxes: &Vec<Rect>) -> usize {
+fn solve_part_1(boxes: &[Rect]) -> usize {
     boxes.iter().fold(0, |acc, rect| {
         acc + (rect.surface_area() + rect.min_face_area())
     })
 }
 
-fn solve_part_2(boxes: &Vec<Rect>) -> usize {
+fn solve_part_2(boxes: &[Rect]) -> usize {
     boxes.iter().fold(0, |acc, rect| {
         acc + (rect.min_perimeter() + rect.cubic())
     })
@@ -83,21 +94,21 @@ mod tests {
         assert_eq!(Rect { l: 2, w: 3, h: 4}.min_face_area(), 6);
         assert_eq!(Rect { l: 1, w: 1, h: 10}.surface_area(), 42);
         assert_eq!(Rect { l: 1, w: 1, h: 10}.min_face_area(), 1);
-        assert_eq!(solve_part_1(&vec![Rect { l: 2, w: 3, h: 4}]), 58);
-        assert_eq!(solve_part_1(&vec![Rect { l: 1, w: 1, h: 10}]), 43);
+        assert_eq!(solve_part_1(&[Rect { l: 2, w: 3, h: 4}]), 58);
+        assert_eq!(solve_part_1(&[Rect { l: 1, w: 1, h: 10}]), 43);
 
 
-        assert_eq!(solve_part_1(&vec![Rect { l: 2, w: 3, h: 4}, Rect { l: 1, w: 1, h: 10}]), 101);
+        assert_eq!(solve_part_1(&[Rect { l: 2, w: 3, h: 4}, Rect { l: 1, w: 1, h: 10}]), 101);
 
 
         assert_eq!(Rect { l: 2, w: 3, h: 4}.min_perimeter(), 10);
         assert_eq!(Rect { l: 2, w: 3, h: 4}.cubic(), 24);
         assert_eq!(Rect { l: 1, w: 1, h: 10}.min_perimeter(), 4);
         assert_eq!(Rect { l: 1, w: 1, h: 10}.cubic(), 10);
-        assert_eq!(solve_part_2(&vec![Rect { l: 2, w: 3, h: 4}]), 34);
-        assert_eq!(solve_part_2(&vec![Rect { l: 1, w: 1, h: 10}]), 14);
+        assert_eq!(solve_part_2(&[Rect { l: 2, w: 3, h: 4}]), 34);
+        assert_eq!(solve_part_2(&[Rect { l: 1, w: 1, h: 10}]), 14);
 
 
-        assert_eq!(solve_part_2(&vec![Rect { l: 2, w: 3, h: 4}, Rect { l: 1, w: 1, h: 10}]), 48);
+        assert_eq!(solve_part_2(&[Rect { l: 2, w: 3, h: 4}, Rect { l: 1, w: 1, h: 10}]), 48);
     }
 }
@@ -1,78 +1,164 @@
-use std::collections::HashSet;
-use std::fs;
-use anyhow::{bail, Result};
+use std::collections::{BTreeSet, HashSet};
+use anyhow::Result;
+use aoc_core::parse::{ self, Input, ParseError };
+use aoc_core::{ Direction, Errors, Solution, SparseGrid };
 
-pub fn execute() -> Result<()> {
-    let input = fs::read("./inputs/3.txt")?;
-    let input = &input[..];
+use crate::Coord;
 
-    println!("# Day 3");
-    println!("Part 1: {:?}", solve_part_1(input)?);
-    println!("Part 2: {:?}", solve_part_2(input)?);
+pub struct Day3;
 
-    Ok(())
+impl Solution for Day3 {
+    type Input = Vec<Direction>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::all(input.trim_end(), parse_moves).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(solve_part_2(input))
+    }
 }
 
+/// Moves like `^>v<`, one character each.
+fn parse_moves(input: &mut Input) -> Result<Vec<Direction>, ParseError> {
+    let mut moves = vec![];
+
+    while !input.is_empty() {
+        moves.push(match input.one_of(&["^", ">", "<", "v"])? {
+            "^" => Direction::Up,
+            ">" => Direction::Right,
+            "<" => Direction::Left,
+            _ => Direction::Down
+        });
+    }
+
+    Ok(moves)
+}
+
+fn solve_part_1(input: &[Direction]) -> usize {
+    Deliveries::simulate(input, 1).houses()
+}
+
+fn solve_part_2(input: &[Direction]) -> usize {
+    Deliveries::simulate(input, 2).houses()
+}
+
+/// What happened at one house.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct House {
+    pub presents: usize,
+    /// Which santas stopped by, numbered in turn order from 0.
+    pub santas: BTreeSet<usize>
+}
 
-fn solve_part_1(input: &[u8]) -> Result<usize> {
-    let mut map = HashSet::new();
+/// Every house that got a present when santas take turns following the
+/// moves. Each santa starts on the same house and leaves a present there.
+pub struct Deliveries {
+    houses: SparseGrid<House>
+}
 
-    let mut x = 0;
-    let mut y = 0;
+impl Deliveries {
+    /// Santa `i` follows moves `i`, `i + santas`, `i + 2 * santas` and so on.
+    pub fn simulate(moves: &[Direction], santas: usize) -> Self {
+        let mut deliveries = Deliveries { houses: SparseGrid::new() };
+        let mut positions = vec![Coord::ORIGIN; santas];
 
-    map.insert((x, y));
+        for santa in 0..santas {
+            deliveries.deliver(Coord::ORIGIN, santa);
+        }
 
-    for i in input {
-        match i {
-            b'^' => y -= 1,
-            b'>' => x += 1,
-            b'<' => x -= 1,
-            b'v' => y += 1,
-            _ => bail!("Invalid move: {:?}", i)
+        for (santa, direction) in (0..santas).cycle().zip(moves) {
+            positions[santa] += direction.offset();
+            deliveries.deliver(positions[santa], santa);
         }
 
-        map.insert((x, y));
+        deliveries
     }
 
-    Ok(map.len())
-}
+    fn deliver(&mut self, at: Coord, santa: usize) {
+        match self.houses.get_mut(at) {
+            Some(house) => {
+                house.presents += 1;
+                house.santas.insert(santa);
+            }
+            None => {
+                self.houses.insert(at, House { presents: 1, santas: BTreeSet::from([santa]) });
+            }
+        }
+    }
 
-fn solve_part_2(input: &[u8]) -> Result<usize> {
-    let mut map = HashSet::new();
+    /// How many houses got at least one present.
+    pub fn houses(&self) -> usize {
+        self.houses.len()
+    }
 
-    let mut coords = [(0, 0), (0, 0)];
-    map.insert((0, 0));
+    pub fn house(&self, at: Coord) -> Option<&House> {
+        self.houses.get(at)
+    }
 
-    for (idx, i) in input.iter().enumerate() {
-        let idx = idx % 2;
-        match i {
-            b'^' => coords[idx].1 -= 1,
-            b'>' => coords[idx].0 += 1,
-            b'<' => coords[idx].0 -= 1,
-            b'v' => coords[idx].1 += 1,
-            _ => bail!("Invalid move: {:?}", i)
-        }
+    /// Every house with the number of presents left there.
+    pub fn visits(&self) -> impl Iterator<Item = (Coord, usize)> + '_ {
+        self.houses.iter().map(|(at, house)| (at, house.presents))
+    }
 
-        map.insert((coords[idx].0, coords[idx].1));
+    /// Houses only one santa went to, however many times.
+    pub fn visited_by_one(&self) -> HashSet<Coord> {
+        self.houses.iter()
+            .filter(|(_, house)| house.santas.len() == 1)
+            .map(|(at, _)| at)
+            .collect()
     }
 
-    Ok(map.len())
+    /// Smallest and largest corner of the visited area, both inclusive.
+    pub fn bounds(&self) -> Option<(Coord, Coord)> {
+        self.houses.bounds()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn moves(input: &str) -> Vec<Direction> {
+        Day3::parse(input).unwrap()
+    }
+
     #[test]
     fn examples() {
-        assert_eq!(solve_part_1(">".as_bytes()).unwrap(), 2);
-        assert_eq!(solve_part_1("^>v<".as_bytes()).unwrap(), 4);
-        assert_eq!(solve_part_1("^v^v^v^v^v".as_bytes()).unwrap(), 2);
+        assert_eq!(solve_part_1(&moves(">")), 2);
+        assert_eq!(solve_part_1(&moves("^>v<")), 4);
+        assert_eq!(solve_part_1(&moves("^v^v^v^v^v")), 2);
 
-        assert_eq!(solve_part_2("^v".as_bytes()).unwrap(), 3);
-        assert_eq!(solve_part_2("^>v<".as_bytes()).unwrap(), 3);
-        assert_eq!(solve_part_2("^v^v^v^v^v".as_bytes()).unwrap(), 11);
+        assert_eq!(solve_part_2(&moves("^v")), 3);
+        assert_eq!(solve_part_2(&moves("^>v<")), 3);
+        assert_eq!(solve_part_2(&moves("^v^v^v^v^v")), 11);
+    }
 
+    #[test]
+    fn many_santas() {
+        let deliveries = Deliveries::simulate(&moves("^>v<^>"), 3);
+
+        // Santa 0 goes up then left, 1 right then up, 2 down then right
+        assert_eq!(deliveries.houses(), 7);
+        assert_eq!(deliveries.house(Coord::ORIGIN).map(|h| h.presents), Some(3));
+        assert_eq!(deliveries.visits().map(|(_, presents)| presents).sum::<usize>(), 9);
+        assert_eq!(
+            deliveries.visited_by_one(),
+            HashSet::from([
+                Coord::new(0, -1), Coord::new(-1, -1), Coord::new(1, 0),
+                Coord::new(1, -1), Coord::new(0, 1), Coord::new(1, 1)
+            ])
+        );
+        assert_eq!(deliveries.bounds(), Some((Coord::new(-1, -1), Coord::new(1, 1))));
+
+        let alone = Deliveries::simulate(&moves("^v"), 1);
+        assert_eq!(alone.house(Coord::ORIGIN).map(|h| h.presents), Some(2));
+        assert_eq!(alone.visited_by_one().len(), 2);
     }
 }
-
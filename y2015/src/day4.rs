@@ -1,65 +1,123 @@
-use std::fs;
 use anyhow::Result;
+use aoc_core::{Errors, Solution};
 use md5::{Md5, Digest};
+use rayon::prelude::*;
 
-pub fn execute() -> Result<()> {
-    let input = fs::read_to_string("./inputs/4.txt")?;
+pub struct Day4;
 
-    println!("# Day 4");
-    println!("Part 1: {:?}", solve_part_1(&input));
-    println!("Part 2: {:?}", solve_part_2(&input));
+impl Solution for Day4 {
+    type Input = Md5;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    Ok(())
+    /// The secret key, already hashed as every nonce is hashed after it.
+    fn parse(input: &str) -> Result<Self::Input> {
+        let key = input.trim();
+        if key.is_empty() {
+            return Err(Errors::MissingInput("no secret key".to_string()).into());
+        }
+
+        Ok(Md5::new_with_prefix(key))
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(solve_part_1(input)?)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(solve_part_2(input)?)
+    }
 }
 
-fn solve_part_1(input: &str) -> usize {
-    let mut answer = 0;
+fn solve_part_1(key: &Md5) -> Result<usize, Errors> {
+    find_nonce(key, 5)
+}
 
-    loop {
-        let input = format!("{}{}", input, answer);
-        let mut hasher = Md5::new();
-        hasher.update(input.as_bytes());
-
-        let result = &hasher.finalize()[..];
-        let result = hex::encode(result);
-        let result = result.as_bytes();
-
-        if &result[..5] == "00000".as_bytes() {
-            return answer;
-        } else {
-            answer += 1;
-        }
+fn solve_part_2(key: &Md5) -> Result<usize, Errors> {
+    find_nonce(key, 6)
+}
+
+/// Nonces handed to the threads at a time. The lowest hit in a batch is the
+/// answer, so work never runs far past it.
+const BATCH: usize = 1 << 16;
+
+/// The lowest nonce whose hash, with the key hashed into `prefix` in front,
+/// starts with `zeros` zero hex digits.
+fn find_nonce(prefix: &Md5, zeros: usize) -> Result<usize, Errors> {
+    if zeros > 32 {
+        let message = format!("an MD5 digest has 32 hex digits, not {}", zeros);
+        return Err(Errors::InvalidState(message));
     }
+
+    (0..usize::MAX)
+        .step_by(BATCH)
+        .find_map(|start| {
+            (start..start.saturating_add(BATCH))
+                .into_par_iter()
+                .find_first(|nonce| {
+                    let mut buffer = [0; 20];
+                    let mut hasher = prefix.clone();
+                    hasher.update(decimal(*nonce, &mut buffer));
+
+                    has_leading_zeros(&hasher.finalize(), zeros)
+                })
+        })
+        .ok_or_else(|| Errors::InvalidState("nonces ran out before a hash matched".to_string()))
 }
 
-fn solve_part_2(input: &str) -> usize {
-    let mut answer = 0;
+/// `n` written out in ASCII digits at the end of `buffer`.
+fn decimal(mut n: usize, buffer: &mut [u8; 20]) -> &[u8] {
+    let mut start = buffer.len();
 
     loop {
-        let input = format!("{}{}", input, answer);
-        let mut hasher = Md5::new();
-        hasher.update(input.as_bytes());
-
-        let result = &hasher.finalize()[..];
-        let result = hex::encode(result);
-        let result = result.as_bytes();
-
-        if &result[..6] == "000000".as_bytes() {
-            return answer;
-        } else {
-            answer += 1;
+        start -= 1;
+        buffer[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+
+        if n == 0 {
+            return &buffer[start..];
         }
     }
 }
 
+/// Whether the first `zeros` hex digits of `digest` are all `0`, checked on
+/// the bytes without encoding them.
+fn has_leading_zeros(digest: &[u8], zeros: usize) -> bool {
+    let whole = zeros / 2;
+
+    let odd_nibble_is_zero = zeros.is_multiple_of(2) || digest[whole] >> 4 == 0;
+
+    digest[..whole].iter().all(|byte| *byte == 0) && odd_nibble_is_zero
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn examples() {
-        assert_eq!(solve_part_1("abcdef"), 609043);
-        assert_eq!(solve_part_1("pqrstuv"), 1048970);
+        let key = Day4::parse("abcdef\n").unwrap();
+        assert_eq!(solve_part_1(&key).unwrap(), 609043);
+        let other = Day4::parse("pqrstuv").unwrap();
+        assert_eq!(solve_part_1(&other).unwrap(), 1048970);
+
+        assert_eq!(
+            find_nonce(&key, 33).unwrap_err().to_string(),
+            "Invalid State: `an MD5 digest has 32 hex digits, not 33`"
+        );
     }
 
+    #[test]
+    fn leading_zeros() {
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x0f, 0xff], 5));
+        assert!(!has_leading_zeros(&[0x00, 0x00, 0x1f, 0xff], 5));
+        assert!(has_leading_zeros(&[0x00, 0x00, 0x00, 0xff], 6));
+        assert!(!has_leading_zeros(&[0x00, 0x00, 0x0f, 0xff], 6));
+        assert!(has_leading_zeros(&[0xff], 0));
+
+        let mut buffer = [0; 20];
+        assert_eq!(decimal(0, &mut buffer), b"0");
+        assert_eq!(decimal(609043, &mut buffer), b"609043");
+        assert_eq!(decimal(usize::MAX, &mut buffer), usize::MAX.to_string().as_bytes());
+    }
 }
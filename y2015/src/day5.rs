@@ -1,16 +1,25 @@
 use std::collections::{HashMap, HashSet};
-use std::fs;
 use anyhow::Result;
+use aoc_core::Solution;
 
-pub fn execute() -> Result<()> {
-    let input = fs::read_to_string("./inputs/5.txt")?;
-    let input = input.split('\n').collect::<Vec<&str>>();
+pub struct Day5;
 
-    println!("# Day 5");
-    println!("Part 1: {:?}", solve_part_1(&input));
-    println!("Part 2: {:?}", solve_part_2(&input));
+impl Solution for Day5 {
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.split('\n').map(String::from).collect())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(solve_part_2(input))
+    }
 }
 
 fn is_nice_part_1(input: &str) -> bool {
@@ -48,10 +57,7 @@ fn dead_set_bad(prev: Option<&u8>, c: &u8) -> bool {
 }
 
 fn is_vowel(c: &u8) -> bool {
-    match c {
-        b'a' | b'e' | b'i' | b'o' | b'u' => true,
-        _ => false
-    }
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u')
 }
 
 fn is_nice_part_2(input: &str) -> bool {
@@ -84,11 +90,11 @@ fn is_nice_part_2(input: &str) -> bool {
     pair_appearance && repeats_with_letter
 }
 
-fn solve_part_1(input: &Vec<&str>) -> usize {
+fn solve_part_1(input: &[String]) -> usize {
     input.iter().filter(|c| is_nice_part_1(c)).count()
 }
 
-fn solve_part_2(input: &Vec<&str>) -> usize {
+fn solve_part_2(input: &[String]) -> usize {
     input.iter().filter(|c| is_nice_part_2(c)).count()
 }
 
@@ -99,18 +105,18 @@ mod tests {
 
     #[test]
     fn examples() {
-        assert_eq!(is_nice_part_1("ugknbfddgicrmopn"), true);
-        assert_eq!(is_nice_part_1("aaa"), true);
-        assert_eq!(is_nice_part_1("jchzalrnumimnmhp"), false);
-        assert_eq!(is_nice_part_1("haegwjzuvuyypxyu"), false);
-        assert_eq!(is_nice_part_1("dvszwmarrgswjxmb"), false);
-
-        assert_eq!(is_nice_part_2("qjhvhtzxzqqjkmpb"), true);
-        assert_eq!(is_nice_part_2("aaa"), false);
-        assert_eq!(is_nice_part_2("aaaa"), true);
-        assert_eq!(is_nice_part_2("xxyxx"), true);
-        assert_eq!(is_nice_part_2("uurcxstgmygtbstg"), false);
-        assert_eq!(is_nice_part_2("ieodomkazucvgmuy"), false);
+        assert!(is_nice_part_1("ugknbfddgicrmopn"));
+        assert!(is_nice_part_1("aaa"));
+        assert!(!is_nice_part_1("jchzalrnumimnmhp"));
+        assert!(!is_nice_part_1("haegwjzuvuyypxyu"));
+        assert!(!is_nice_part_1("dvszwmarrgswjxmb"));
+
+        assert!(is_nice_part_2("qjhvhtzxzqqjkmpb"));
+        assert!(!is_nice_part_2("aaa"));
+        assert!(is_nice_part_2("aaaa"));
+        assert!(is_nice_part_2("xxyxx"));
+        assert!(!is_nice_part_2("uurcxstgmygtbstg"));
+        assert!(!is_nice_part_2("ieodomkazucvgmuy"));
     }
 }
 
@@ -1,143 +1,355 @@
+use std::io::{self, Write};
+use std::ops::Range;
+
 use anyhow::Result;
-use std::{cmp::max, fs};
-
-pub fn execute() -> Result<()> {
-    let input = fs::read_to_string("./inputs/6.txt")?;
-    let input = input
-        .split('\n')
-        .map(Instruction::from)
-        .collect::<Vec<Instruction>>();
-
-    println!("# Day 6");
-    println!("Part 1: {:?}", solve_part_1(&input));
-    println!("Part 2: {:?}", solve_part_2(&input));
-    Ok(())
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Grid, Point, RenderingSolution, Solution,
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Instruction>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, Instruction::parse).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(solve_part_1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        Ok(solve_part_2(input))
+    }
 }
 
-struct Instruction {
-    action: Action,
-    left_bot: Coord,
-    right_top: Coord,
+/// Part 1 draws which lights are lit, part 2 how bright they are.
+impl RenderingSolution for Day6 {
+    fn render(input: &Self::Input, part: u8, mut out: &mut dyn Write) -> Result<()> {
+        if part == 1 {
+            let lights = Lights::switch(input);
+            let (width, height) = lights.extent();
+            lights.write_pbm(&mut out, width, height)?;
+        } else {
+            let lights = Lights::dim(input);
+            let (width, height) = lights.extent();
+            lights.write_pgm(&mut out, width, height)?;
+        }
+
+        Ok(())
+    }
 }
 
-struct Coord {
-    x: usize,
-    y: usize,
+pub struct Instruction {
+    action: Action,
+    left_bot: Point<usize>,
+    right_top: Point<usize>,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 enum Action {
     ON,
     TOOGLE,
     OFF,
 }
 
-impl From<&str> for Instruction {
-    fn from(input: &str) -> Self {
-        let mut cursor = 0;
-        let split: Vec<&str> = input.split(' ').collect();
-        let action = match split[cursor] {
-            "turn" => {
-                cursor += 1;
-                match split[cursor] {
-                    "on" => Action::ON,
-                    "off" => Action::OFF,
-                    _ => unreachable!("not meant to happen!"),
-                }
-            }
-            "toggle" => Action::TOOGLE,
-            _ => unreachable!("not meant to happen!!"),
+impl Instruction {
+    /// The columns of the instruction's rectangle, corners included.
+    fn columns(&self) -> Range<usize> {
+        self.left_bot.x..self.right_top.x + 1
+    }
+
+    /// The rows of the instruction's rectangle, corners included.
+    fn rows(&self) -> Range<usize> {
+        self.left_bot.y..self.right_top.y + 1
+    }
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = Errors;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Ok(parse::all(input, Instruction::parse)?)
+    }
+}
+
+impl Instruction {
+    /// An instruction like `turn on 0,0 through 999,999`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let action = match input.one_of(&["turn on ", "turn off ", "toggle "])? {
+            "turn on " => Action::ON,
+            "turn off " => Action::OFF,
+            _ => Action::TOOGLE,
+        };
+
+        let left_bot = input.point(",")?;
+        input.tag(" through ")?;
+        let right_top = input.point(",")?;
+
+        Ok(Instruction {
+            action,
+            left_bot,
+            right_top,
+        })
+    }
+}
+
+fn solve_part_1(input: &[Instruction]) -> u64 {
+    Lights::switch(input).lit()
+}
+
+fn solve_part_2(input: &[Instruction]) -> u64 {
+    Lights::dim(input).brightness()
+}
+
+/// The lights after following every instruction, kept as the rectangles cut
+/// out by the instructions' edges rather than one by one. Every instruction
+/// covers a rectangle whole or misses it, so the work depends on how many
+/// instructions there are and not on how far apart their corners are.
+pub struct Lights<T> {
+    /// Where the rectangles start along each axis, plus where the last ends.
+    xs: Vec<usize>,
+    ys: Vec<usize>,
+    regions: Grid<T>,
+    /// The state of lights no instruction touches.
+    fill: T,
+}
+
+impl<T: Clone> Lights<T> {
+    fn follow(instructions: &[Instruction], fill: T, step: impl Fn(&Action, &mut T)) -> Self {
+        let instructions: Vec<&Instruction> = instructions
+            .iter()
+            .filter(|inst| !inst.columns().is_empty() && !inst.rows().is_empty())
+            .collect();
+
+        let edges = |range: fn(&Instruction) -> Range<usize>| {
+            let mut edges: Vec<usize> = instructions
+                .iter()
+                .flat_map(|inst| {
+                    let range = range(inst);
+                    [range.start, range.end]
+                })
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            edges
         };
 
-        cursor += 1;
+        let xs = edges(Instruction::columns);
+        let ys = edges(Instruction::rows);
+        let mut regions = Grid::new(
+            xs.len().saturating_sub(1),
+            ys.len().saturating_sub(1),
+            fill.clone(),
+        );
 
-        let left_coord = Coord::from(split[cursor]);
+        let index = |edges: &[usize], edge: usize| edges.partition_point(|e| *e < edge);
 
-        cursor += 2; // skipping the 'through'
+        for inst in instructions {
+            let (columns, rows) = (inst.columns(), inst.rows());
 
-        let right_coord = Coord::from(split[cursor]);
+            for y in index(&ys, rows.start)..index(&ys, rows.end) {
+                for x in index(&xs, columns.start)..index(&xs, columns.end) {
+                    step(&inst.action, &mut regions[Point::new(x, y)]);
+                }
+            }
+        }
 
-        Instruction {
-            action: action,
-            left_bot: left_coord,
-            right_top: right_coord,
+        Lights {
+            xs,
+            ys,
+            regions,
+            fill,
         }
     }
-}
 
-impl From<&str> for Coord {
-    fn from(input: &str) -> Self {
-        let split: Vec<&str> = input.split(',').collect();
+    /// Every rectangle with the number of lights in it.
+    fn regions(&self) -> impl Iterator<Item = (u64, &T)> + '_ {
+        self.regions.iter().map(|(point, light)| {
+            let width = self.xs[point.x + 1] - self.xs[point.x];
+            let height = self.ys[point.y + 1] - self.ys[point.y];
+
+            ((width * height) as u64, light)
+        })
+    }
+
+    /// How many lights across and down reach every light an instruction
+    /// touched, counting from the top left corner.
+    pub fn extent(&self) -> (usize, usize) {
+        let last = |edges: &[usize]| edges.last().copied().unwrap_or_default();
+
+        (last(&self.xs), last(&self.ys))
+    }
+
+    /// The state of the light at `point`.
+    pub fn get(&self, point: Point<usize>) -> &T {
+        let region = |edges: &[usize], at: usize| match edges.partition_point(|edge| *edge <= at) {
+            0 => None,
+            i if i == edges.len() => None,
+            i => Some(i - 1),
+        };
 
-        if split.len() != 2 {
-            unreachable!("coord: weird input {:?}", input);
+        match (region(&self.xs, point.x), region(&self.ys, point.y)) {
+            (Some(x), Some(y)) => &self.regions[Point::new(x, y)],
+            _ => &self.fill,
         }
+    }
+
+    /// Writes a binary Netpbm image of the `width` by `height` lights in the
+    /// top left corner: `header`, then `row` called for each row.
+    fn write_netpbm(
+        &self,
+        out: &mut impl Write,
+        header: &str,
+        width: usize,
+        height: usize,
+        mut row: impl FnMut(&mut Vec<u8>, &mut dyn Iterator<Item = &T>),
+    ) -> io::Result<()> {
+        write!(out, "{}", header)?;
 
-        Coord {
-            x: split[0].parse::<usize>().unwrap(),
-            y: split[1].parse::<usize>().unwrap(),
+        let mut bytes = vec![];
+        for y in 0..height {
+            bytes.clear();
+            row(
+                &mut bytes,
+                &mut (0..width).map(|x| self.get(Point::new(x, y))),
+            );
+            out.write_all(&bytes)?;
         }
+
+        Ok(())
     }
 }
 
-fn solve_part_1(input: &Vec<Instruction>) -> usize {
-    let mut grid = [[false; 1000]; 1000];
-
-    input.iter().for_each(|inst| {
-        for i in inst.left_bot.x..=inst.right_top.x {
-            for j in inst.left_bot.y..=inst.right_top.y {
-                grid[i][j] = match inst.action {
-                    Action::ON => true,
-                    Action::OFF => false,
-                    Action::TOOGLE => !grid[i][j],
-                }
+impl Lights<bool> {
+    /// Switches lights on and off by the first reading of the instructions.
+    pub fn switch(instructions: &[Instruction]) -> Self {
+        Lights::follow(instructions, false, |action, light| {
+            *light = match action {
+                Action::ON => true,
+                Action::OFF => false,
+                Action::TOOGLE => !*light,
             }
-        }
-    });
+        })
+    }
+
+    pub fn lit(&self) -> u64 {
+        self.regions()
+            .filter(|(_, light)| **light)
+            .map(|(area, _)| area)
+            .sum()
+    }
+
+    /// Writes the lights as a binary PBM image, lit lights white.
+    pub fn write_pbm(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let header = format!("P4\n{} {}\n", width, height);
 
-    grid.iter()
-        .map(|row| {
-            row.iter()
-                .map(|col| if *col { 1 } else { 0 })
-                .sum::<usize>()
+        self.write_netpbm(out, &header, width, height, |bytes, lights| {
+            // Eight pixels to a byte, a set bit being black
+            let mut lights = lights.peekable();
+            while lights.peek().is_some() {
+                let byte = (0..8).fold(0_u8, |byte, bit| match lights.next() {
+                    Some(false) => byte | 0x80 >> bit,
+                    _ => byte,
+                });
+                bytes.push(byte);
+            }
         })
-        .sum()
+    }
 }
 
-fn solve_part_2(input: &Vec<Instruction>) -> u64 {
-    let mut grid: Vec<Vec<u64>> = vec![];
+impl Lights<u64> {
+    /// Turns brightness up and down by the second reading of the instructions.
+    pub fn dim(instructions: &[Instruction]) -> Self {
+        Lights::follow(instructions, 0, |action, light| match action {
+            Action::ON => *light += 1,
+            Action::OFF => *light = light.saturating_sub(1),
+            Action::TOOGLE => *light += 2,
+        })
+    }
 
-    for i in 0..1000 {
-        grid.push(vec![]);
-        for _j in 0..1000 {
-            grid[i].push(0);
-        }
+    pub fn brightness(&self) -> u64 {
+        self.regions().map(|(area, light)| area * light).sum()
     }
 
-    input.iter().for_each(|inst| {
-        for i in inst.left_bot.x..=inst.right_top.x {
-            for j in inst.left_bot.y..=inst.right_top.y {
-                match inst.action {
-                    Action::ON => grid[i][j] += 1,
-                    Action::OFF => {
-                        if grid[i][j] == 0 {
-                            grid[i][j] = 0;
-                        } else {
-                            grid[i][j] -= 1;
-                        }
-                    }
-                    Action::TOOGLE => grid[i][j] += 2,
+    /// Writes the lights as a binary PGM image, the brightest white.
+    /// Brightness past 65535 is drawn as 65535.
+    pub fn write_pgm(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let max = self
+            .regions
+            .cells()
+            .max()
+            .copied()
+            .unwrap_or_default()
+            .clamp(1, 65535);
+        let header = format!("P5\n{} {}\n{}\n", width, height, max);
+
+        self.write_netpbm(out, &header, width, height, |bytes, lights| {
+            for light in lights {
+                let level = (*light).min(max) as u16;
+
+                // Levels take two bytes, most significant first, past 255
+                if max > 255 {
+                    bytes.extend(level.to_be_bytes());
+                } else {
+                    bytes.push(level as u8);
                 }
             }
-        }
-    });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions() {
+        let instructions = Day6::parse(
+            "turn on 0,0 through 999,999\n\
+             toggle 0,0 through 999,0\n\
+             turn off 499,499 through 500,500\n\
+             toggle 10,10 through 10,2000000",
+        )
+        .unwrap();
 
-    let mut r = 0;
+        let lights = Lights::switch(&instructions);
+        assert_eq!(lights.lit(), 1_000_000 - 1000 - 4 - 990 + 1_999_001);
+        assert!(!*lights.get(Point::new(500, 500)));
+        assert!(*lights.get(Point::new(501, 500)));
+        assert!(*lights.get(Point::new(10, 2000000)));
+        assert!(!*lights.get(Point::new(10, 2000001)));
 
-    grid.iter().for_each(|row| {
-        row.iter().for_each(|col| {
-            r += col;
-        });
-    });
+        let brightness = Lights::dim(&instructions);
+        assert_eq!(
+            brightness.brightness(),
+            1_000_000 + 2000 - 4 + 2 * 1_999_991
+        );
+        assert_eq!(*brightness.get(Point::new(10, 10)), 3);
+    }
+
+    #[test]
+    fn images() {
+        let instructions = Day6::parse("turn on 0,0 through 8,0\ntoggle 1,1 through 1,1").unwrap();
+
+        let mut pbm = vec![];
+        Lights::switch(&instructions)
+            .write_pbm(&mut pbm, 10, 2)
+            .unwrap();
+        assert_eq!(pbm, b"P4\n10 2\n\x00\x40\xbf\xc0");
 
-    r
+        let mut pgm = vec![];
+        Lights::dim(&instructions)
+            .write_pgm(&mut pgm, 3, 2)
+            .unwrap();
+        assert_eq!(pgm, b"P5\n3 2\n2\n\x01\x01\x01\x00\x02\x00");
+
+        let mut pbm = vec![];
+        Day6::render(&instructions, 1, &mut pbm).unwrap();
+        assert_eq!(pbm, b"P4\n9 2\n\x00\x00\xbf\x80");
+    }
 }
@@ -0,0 +1,262 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Circuit;
+    type Part1 = u16;
+    type Part2 = u16;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Circuit::try_from(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        Ok(input.clone().signal("a")?)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        let mut circuit = input.clone();
+        let a = circuit.signal("a")?;
+        circuit.set("b", a);
+
+        Ok(circuit.signal("a")?)
+    }
+}
+
+/// A gate input: either a constant or whatever is on another wire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Signal {
+    Value(u16),
+    Wire(String),
+}
+
+impl Signal {
+    /// A number like `123` or a wire name like `lx`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        if let Some(value) = input.attempt(|i| i.unsigned()) {
+            return Ok(Signal::Value(value));
+        }
+
+        Ok(Signal::Wire(wire_name(input)?.to_string()))
+    }
+}
+
+/// What drives a wire. Every signal is 16 bits wide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gate {
+    Direct(Signal),
+    And(Signal, Signal),
+    Or(Signal, Signal),
+    LShift(Signal, Signal),
+    RShift(Signal, Signal),
+    Not(Signal),
+}
+
+impl Gate {
+    /// The left side of a connection, like `x AND y` or `NOT x`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        if input.attempt(|i| i.tag("NOT ")).is_some() {
+            return Ok(Gate::Not(Signal::parse(input)?));
+        }
+
+        let left = Signal::parse(input)?;
+        let operator = input.attempt(|i| i.one_of(&[" AND ", " OR ", " LSHIFT ", " RSHIFT "]));
+        let Some(operator) = operator else {
+            return Ok(Gate::Direct(left));
+        };
+
+        let right = Signal::parse(input)?;
+        Ok(match operator {
+            " AND " => Gate::And(left, right),
+            " OR " => Gate::Or(left, right),
+            " LSHIFT " => Gate::LShift(left, right),
+            _ => Gate::RShift(left, right),
+        })
+    }
+
+    fn inputs(&self) -> Vec<&Signal> {
+        match self {
+            Gate::Direct(signal) | Gate::Not(signal) => vec![signal],
+            Gate::And(left, right)
+            | Gate::Or(left, right)
+            | Gate::LShift(left, right)
+            | Gate::RShift(left, right) => vec![left, right],
+        }
+    }
+
+    /// The output given the values of [`Gate::inputs`], in the same order.
+    fn apply(&self, values: &[u16]) -> u16 {
+        match self {
+            Gate::Direct(_) => values[0],
+            Gate::Not(_) => !values[0],
+            Gate::And(..) => values[0] & values[1],
+            Gate::Or(..) => values[0] | values[1],
+            Gate::LShift(..) => values[0].checked_shl(values[1].into()).unwrap_or(0),
+            Gate::RShift(..) => values[0].checked_shr(values[1].into()).unwrap_or(0),
+        }
+    }
+}
+
+/// Lower case letters naming a wire.
+fn wire_name<'a>(input: &mut Input<'a>) -> Result<&'a str, ParseError> {
+    input.take_while1("a wire", |c| c.is_ascii_lowercase())
+}
+
+/// A connection like `x AND y -> d`.
+fn parse_connection(input: &mut Input) -> Result<(String, Gate), ParseError> {
+    let gate = Gate::parse(input)?;
+    input.tag(" -> ")?;
+    let wire = wire_name(input)?;
+
+    Ok((wire.to_string(), gate))
+}
+
+/// Wires driven by gates. Signals are worked out only when asked for and
+/// remembered until the circuit changes.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    gates: HashMap<String, Gate>,
+    signals: HashMap<String, u16>,
+}
+
+impl TryFrom<&str> for Circuit {
+    type Error = Errors;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut gates = HashMap::new();
+
+        for (i, (wire, gate)) in parse::lines(value, parse_connection)?
+            .into_iter()
+            .enumerate()
+        {
+            if gates.contains_key(&wire) {
+                let message = format!("wire `{}` is driven twice", wire);
+                return Err(ParseError::at_line(i + 1, message).into());
+            }
+
+            gates.insert(wire, gate);
+        }
+
+        if gates.is_empty() {
+            return Err(Errors::MissingInput("no gates in the circuit".to_string()));
+        }
+
+        Ok(Circuit {
+            gates,
+            signals: HashMap::new(),
+        })
+    }
+}
+
+impl Circuit {
+    /// The signal on `wire`.
+    pub fn signal(&mut self, wire: &str) -> Result<u16, Errors> {
+        let mut visiting = HashSet::new();
+
+        evaluate(&self.gates, &mut self.signals, &mut visiting, wire)
+    }
+
+    /// Drives `wire` with `value` in place of its gate, forgetting every
+    /// signal worked out so far.
+    pub fn set(&mut self, wire: &str, value: u16) {
+        self.gates
+            .insert(wire.to_string(), Gate::Direct(Signal::Value(value)));
+        self.signals.clear();
+    }
+}
+
+/// Works out `wire` from its gate's inputs, `visiting` holding the wires
+/// still waiting on it so loops are caught instead of recursing forever.
+fn evaluate<'g>(
+    gates: &'g HashMap<String, Gate>,
+    signals: &mut HashMap<String, u16>,
+    visiting: &mut HashSet<&'g str>,
+    wire: &str,
+) -> Result<u16, Errors> {
+    if let Some(value) = signals.get(wire) {
+        return Ok(*value);
+    }
+
+    let (wire, gate) = gates
+        .get_key_value(wire)
+        .ok_or_else(|| Errors::InvalidState(format!("no gate drives wire `{}`", wire)))?;
+
+    if !visiting.insert(wire) {
+        return Err(Errors::InvalidState(format!(
+            "wire `{}` depends on itself",
+            wire
+        )));
+    }
+
+    let values = gate
+        .inputs()
+        .into_iter()
+        .map(|signal| match signal {
+            Signal::Value(value) => Ok(*value),
+            Signal::Wire(input) => evaluate(gates, signals, visiting, input),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let value = gate.apply(&values);
+    visiting.remove(wire.as_str());
+    signals.insert(wire.clone(), value);
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Circuit;
+
+    const EXAMPLE: &str = "123 -> x\n\
+                           456 -> y\n\
+                           x AND y -> d\n\
+                           x OR y -> e\n\
+                           x LSHIFT 2 -> f\n\
+                           y RSHIFT 2 -> g\n\
+                           NOT x -> h\n\
+                           NOT y -> i";
+
+    #[test]
+    fn test_example() {
+        let mut circuit = Circuit::try_from(EXAMPLE).unwrap();
+        let signals: Vec<u16> = ["d", "e", "f", "g", "h", "i", "x", "y"]
+            .iter()
+            .map(|wire| circuit.signal(wire).unwrap())
+            .collect();
+
+        assert_eq!(signals, vec![72, 507, 492, 114, 65412, 65079, 123, 456]);
+
+        circuit.set("x", 1);
+        assert_eq!(circuit.signal("d").unwrap(), 0);
+        assert_eq!(circuit.signal("e").unwrap(), 457);
+    }
+
+    #[test]
+    fn test_broken_circuits() {
+        let mut circuit = Circuit::try_from("a -> b\nb AND 1 -> c\nc -> a").unwrap();
+        assert_eq!(
+            circuit.signal("c").unwrap_err().to_string(),
+            "Invalid State: `wire `c` depends on itself`"
+        );
+
+        let mut circuit = Circuit::try_from("x OR z -> y").unwrap();
+        assert_eq!(
+            circuit.signal("y").unwrap_err().to_string(),
+            "Invalid State: `no gate drives wire `x``"
+        );
+
+        let err = Circuit::try_from("1 -> a\n2 -> a").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2: wire `a` is driven twice`"
+        );
+    }
+}
@@ -5,3 +5,23 @@ pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
+
+use std::path::Path;
+
+use aoc_core::{Point, Registry};
+
+pub fn register(registry: &mut Registry) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    registry.add_input_dir(2015, dir.join("inputs"));
+    registry.add_answers_file(2015, dir.join("answers.toml"));
+
+    registry.register_streaming::<day1::Day1>(2015, 1);
+    registry.register::<day2::Day2>(2015, 2);
+    registry.register::<day3::Day3>(2015, 3);
+    registry.register::<day4::Day4>(2015, 4);
+    registry.register::<day5::Day5>(2015, 5);
+    registry.register_rendering::<day6::Day6>(2015, 6);
+    // Day 7 is left out until its input is checked in with known answers
+}
+
+type Coord = Point<isize>;
@@ -1,13 +1,9 @@
-use anyhow::Result;
-use y2015::{day1, day2, day3, day4, day5, day6};
+use std::process::ExitCode;
+use aoc_core::Registry;
 
-fn main() -> Result<()> {
-    day1::execute()?;
-    day2::execute()?;
-    day3::execute()?;
-    // day4::execute()?;
-    day5::execute()?;
-    day6::execute()?;
+fn main() -> ExitCode {
+    let mut registry = Registry::new();
+    y2015::register(&mut registry);
 
-    Ok(())
+    aoc_core::cli::main(registry)
 }
@@ -1,40 +1,118 @@
-use std::{collections::BinaryHeap, fs};
+use std::{cmp::Reverse, collections::BinaryHeap, io::BufRead};
 
-use anyhow::Ok;
+use aoc_core::{parse, stream, Errors, Solution, StreamingSolution};
 
-pub fn exec() -> anyhow::Result<()> {
-    let input = fs::read_to_string("./inputs/day1.txt")?;
-    let input = &input[..];
+pub struct Day1;
 
-    solve_part1(input)?;
-    solve_part2(input)?;
+impl Solution for Day1 {
+    type Input = Vec<u64>;
+    type Part1 = u64;
+    type Part2 = u64;
 
-    Ok(())
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let elves = parse::blocks(input, |input| {
+            input.separated("\n", |i| i.unsigned::<u64>())
+        });
+
+        Ok(elves
+            .map_err(Errors::from)?
+            .iter()
+            .map(|calories| calories.iter().sum())
+            .collect())
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        solve_part1(input.iter().copied())
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        solve_part2(input.iter().copied())
+    }
 }
 
-fn calculate_score(input: &str) -> u64 {
-    input.split('\n').map(|i| i.parse::<u64>().unwrap()).sum()
+impl StreamingSolution for Day1 {
+    fn part1_stream(input: &mut dyn BufRead) -> anyhow::Result<Self::Part1> {
+        stream::process(elves(input), |elves| solve_part1(elves))?
+    }
+
+    fn part2_stream(input: &mut dyn BufRead) -> anyhow::Result<Self::Part2> {
+        stream::process(elves(input), |elves| solve_part2(elves))?
+    }
+}
+
+/// The calories each elf carries, added up a line at a time.
+fn elves(input: &mut dyn BufRead) -> impl Iterator<Item = anyhow::Result<u64>> + '_ {
+    // A blank line after the last elf flushes it like the others
+    let lines = stream::lines(input, |i| Ok(i.attempt(|i| i.unsigned::<u64>())));
+    let mut carrying = None;
+
+    lines.chain([Ok(None)]).filter_map(move |line| match line {
+        Ok(Some(calories)) => {
+            *carrying.get_or_insert(0) += calories;
+            None
+        }
+        Ok(None) => carrying.take().map(Ok),
+        Err(err) => Some(Err(err)),
+    })
 }
 
-fn solve_part1(input: &str) -> anyhow::Result<()> {
-    let result = input.split("\n\n").map(calculate_score).max();
-    println!("Day 1-1: {}", result.unwrap());
+fn solve_part1(input: impl IntoIterator<Item = u64>) -> anyhow::Result<u64> {
+    let result = input
+        .into_iter()
+        .max()
+        .ok_or(Errors::MissingInput("no elves carrying calories".into()))?;
 
-    Ok(())
+    Ok(result)
 }
 
-fn solve_part2(input: &str) -> anyhow::Result<()> {
-    let mut result = input
-        .split("\n\n")
-        .map(calculate_score)
-        .collect::<BinaryHeap<u64>>();
+fn solve_part2(input: impl IntoIterator<Item = u64>) -> anyhow::Result<u64> {
+    // Only the three largest are kept, smallest on top
+    let mut result = BinaryHeap::new();
+    let mut elves = 0;
 
-    let mut total = 0;
-    for _ in 0..3 {
-        total += result.pop().unwrap();
+    for calories in input {
+        result.push(Reverse(calories));
+        elves += 1;
+
+        if result.len() > 3 {
+            result.pop();
+        }
+    }
+
+    if elves < 3 {
+        return Err(Errors::MissingInput(format!("only {} elves, need 3", elves)).into());
     }
 
-    println!("Day 1-2: {}", total);
+    Ok(result.into_iter().map(|Reverse(calories)| calories).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_core::{Solution, StreamingSolution};
+
+    use super::Day1;
 
-    Ok(())
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+    #[test]
+    fn test_streaming() {
+        let elves = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(Day1::part1(&elves).unwrap(), 24000);
+        assert_eq!(Day1::part2(&elves).unwrap(), 45000);
+
+        assert_eq!(Day1::part1_stream(&mut EXAMPLE.as_bytes()).unwrap(), 24000);
+        assert_eq!(
+            Day1::part2_stream(&mut EXAMPLE.trim_end().as_bytes()).unwrap(),
+            45000
+        );
+
+        let err = Day1::part1_stream(&mut "100\n\n2oo\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 3, column 2: expected the end of the input`"
+        );
+
+        let err = Day1::part2_stream(&mut "100\n\n\n200\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "Missing Input: `only 2 elves, need 3`");
+    }
 }
@@ -1,180 +1,318 @@
-use std::{cell::RefCell, fs, rc::Rc};
+use std::{
+    borrow::Borrow,
+    io::BufRead,
+    ops::{Index, IndexMut},
+};
 
 use anyhow::Result;
 
-use crate::Errors;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    stream, Errors, Grid, Solution, StreamingSolution,
+};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day10.txt")?;
+pub struct Day10;
 
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+    type Part1 = i64;
+    type Part2 = Grid<bool>;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        let set = InstructionSet::default();
+        Ok(parse::lines(input, |i| set.parse(i)).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let instructions: Vec<Instruction> = input
-        .split("\n")
-        .filter_map(|c| Instruction::try_from(c).ok())
-        .collect();
+impl StreamingSolution for Day10 {
+    fn part1_stream(input: &mut dyn BufRead) -> Result<Self::Part1> {
+        let set = InstructionSet::default();
 
-    let mut cpu = CPU::new();
+        stream::process(stream::lines(input, |i| set.parse(i)), |program| {
+            signal_strength(CPU::new(program))
+        })
+    }
 
-    let mut results = vec![];
+    fn part2_stream(input: &mut dyn BufRead) -> Result<Self::Part2> {
+        let set = InstructionSet::default();
 
-    cpu.exec(
-        instructions.into_iter().rev().collect(),
-        220,
-        |stage| match stage {
-            Stage::During(step, value) => {
-                let step = step + 1;
-                match step {
-                    20 | 60 | 100 | 140 | 180 | 220 => results.push(step as i64 * value),
-                    _ => {}
-                };
-            }
-            _ => {}
-        },
-    );
-    // println!("Results: {:?}", results);
-    println!("Day 10-1: {:?}", results.iter().sum::<i64>());
-    Ok(())
-}
-
-fn solve_part_2(input: &str) -> Result<()> {
-    let instructions: Vec<Instruction> = input
-        .split("\n")
-        .filter_map(|c| Instruction::try_from(c).ok())
-        .collect();
-
-    let mut cpu = CPU::new();
-    let crt = Rc::new(RefCell::new(CRT::new(40, 6)));
-
-    let crt_1 = Rc::clone(&crt);
-    let crt_2 = Rc::clone(&crt);
-
-    cpu.exec(
-        instructions.into_iter().rev().collect(),
-        40 * 6,
-        |stage| match stage {
-            Stage::During(step, _value) => {
-                crt_1.borrow_mut().plot(step as i64);
-            }
-            Stage::EndOfCycle(_step, value) => {
-                crt_2.borrow_mut().move_sprite(value);
-            }
-            _ => {}
-        },
-    );
+        stream::process(stream::lines(input, |i| set.parse(i)), |program| {
+            draw(CPU::new(program))
+        })?
+    }
+}
+
+fn solve_part_1(instructions: &[Instruction]) -> Result<i64> {
+    Ok(signal_strength(CPU::new(instructions)))
+}
+
+fn solve_part_2(instructions: &[Instruction]) -> Result<Grid<bool>> {
+    draw(CPU::new(instructions))
+}
+
+fn signal_strength(cpu: impl Iterator<Item = Stage>) -> i64 {
+    cpu.filter_map(|stage| match stage {
+        Stage::During {
+            cycle, registers, ..
+        } if cycle % 40 == 20 && cycle <= 220 => Some(cycle as i64 * registers[Register::X]),
+        _ => None,
+    })
+    .sum()
+}
+
+fn draw(cpu: impl Iterator<Item = Stage>) -> Result<Grid<bool>> {
+    let mut crt = CRT::new(40, 6);
+
+    for stage in cpu {
+        if let Stage::During {
+            cycle, registers, ..
+        } = stage
+        {
+            crt.move_sprite(registers[Register::X]);
+            crt.plot(cycle as i64 - 1);
+        }
+    }
 
-    crt.borrow().print();
-    println!("Day 10-2: {:?}", "");
-    Ok(())
+    crt.image()
 }
 
-#[derive(Clone, Copy)]
-enum Instruction {
-    Addx(i64),
-    Noop,
+/// The registers an instruction can read and write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    X,
+}
+
+impl Register {
+    const COUNT: usize = 1;
+}
+
+/// The value of every register, indexed by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Registers([i64; Register::COUNT]);
+
+impl Index<Register> for Registers {
+    type Output = i64;
+
+    fn index(&self, register: Register) -> &Self::Output {
+        &self.0[register as usize]
+    }
+}
+
+impl IndexMut<Register> for Registers {
+    fn index_mut(&mut self, register: Register) -> &mut Self::Output {
+        &mut self.0[register as usize]
+    }
+}
+
+/// An opcode: how it is spelled, how many operands follow it, how many
+/// cycles it takes and what it does to the registers once it finishes.
+#[derive(Debug, Clone, Copy)]
+pub struct Op {
+    pub name: &'static str,
+    pub operands: usize,
+    pub cycles: usize,
+    pub execute: fn(&mut Registers, &[i64]),
+}
+
+/// The opcodes a program may use. The default is the handheld's `noop`
+/// and `addx`; more can be added with [`InstructionSet::with`].
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    ops: Vec<Op>,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        InstructionSet::empty()
+            .with(Op {
+                name: "noop",
+                operands: 0,
+                cycles: 1,
+                execute: |_, _| {},
+            })
+            .with(Op {
+                name: "addx",
+                operands: 1,
+                cycles: 2,
+                execute: |registers, operands| registers[Register::X] += operands[0],
+            })
+    }
+}
+
+impl InstructionSet {
+    pub fn empty() -> Self {
+        InstructionSet { ops: vec![] }
+    }
+
+    /// Adds `op`, replacing any opcode of the same name.
+    ///
+    /// # Panics
+    ///
+    /// If `op` takes no cycles.
+    pub fn with(mut self, op: Op) -> Self {
+        assert!(op.cycles > 0, "`{}` must take at least one cycle", op.name);
+
+        self.ops.retain(|known| known.name != op.name);
+        self.ops.push(op);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Op> {
+        self.ops.iter().find(|op| op.name == name)
+    }
+
+    /// An opcode followed by its signed operands, like `addx -5`.
+    pub fn parse(&self, input: &mut Input) -> Result<Instruction, ParseError> {
+        let start = *input;
+        let name = input.word()?;
+        let op = *self
+            .get(name)
+            .ok_or_else(|| start.error(format!("unknown instruction `{}`", name)))?;
+
+        let operands = (0..op.operands)
+            .map(|_| {
+                input.tag(" ")?;
+                input.signed()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Instruction { op, operands })
+    }
 }
 
-enum Stage {
-    Start(usize, i64),
-    During(usize, i64),
-    EndOfCycle(usize, i64),
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    op: Op,
+    operands: Vec<i64>,
 }
 
 impl TryFrom<&str> for Instruction {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<&str> = value.split(' ').collect();
+        let set = InstructionSet::default();
+        Ok(parse::all(value, |i| set.parse(i))?)
+    }
+}
 
-        let cmd = split[0];
+/// What the CPU reports as it runs. Cycles are counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// `cycle` is under way on the instruction at `pc`; `registers` still
+    /// hold their values from before it.
+    During {
+        cycle: usize,
+        pc: usize,
+        registers: Registers,
+    },
+    /// The instruction at `pc` finished at the end of `cycle`, leaving
+    /// `registers` behind.
+    Retired {
+        cycle: usize,
+        pc: usize,
+        registers: Registers,
+    },
+}
 
-        match cmd {
-            "addx" => {
-                let value: i64 = split[1].parse()?;
-                Ok(Self::Addx(value))
-            }
-            "noop" => Ok(Self::Noop),
-            _ => Err(Errors::ParseError("invalid instruction".into())),
+/// Runs a program in order, one cycle at a time, yielding a [`Stage`] for
+/// every cycle and every instruction it finishes. Stops after the last
+/// instruction. `X` starts at 1.
+///
+/// Instructions are fetched only when the previous one retires, so a
+/// program can be streamed in as it runs.
+#[allow(clippy::upper_case_acronyms)]
+pub struct CPU<P: Iterator> {
+    program: P,
+    /// The instruction at `pc`, once fetched.
+    current: Option<P::Item>,
+    pc: usize,
+    cycle: usize,
+    /// Cycles spent so far on the instruction at `pc`.
+    elapsed: usize,
+    registers: Registers,
+    retired: Option<Stage>,
+}
+
+impl<P> CPU<P>
+where
+    P: Iterator,
+    P::Item: Borrow<Instruction>,
+{
+    pub fn new(program: impl IntoIterator<IntoIter = P>) -> Self {
+        let mut registers = Registers::default();
+        registers[Register::X] = 1;
+
+        CPU {
+            program: program.into_iter(),
+            current: None,
+            pc: 0,
+            cycle: 0,
+            elapsed: 0,
+            registers,
+            retired: None,
         }
     }
+
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
 }
 
-struct CPU {
-    register: i64,
-}
-
-impl CPU {
-    fn new() -> Self {
-        CPU { register: 1 }
-    }
-
-    fn peek(&self) -> i64 {
-        self.register
-    }
-
-    fn exec<F>(&mut self, instructions: Vec<Instruction>, max_steps: usize, mut callback: F)
-    where
-        F: FnMut(Stage),
-    {
-        let mut instructions: Vec<Instruction> = instructions.clone();
-        let mut pending_instruction: Option<(usize, Instruction)> = None;
-        for i in 0..max_steps {
-            callback(Stage::Start(i, self.register));
-
-            if pending_instruction.is_none() {
-                // print!("Start cycle {}: begin executing ", i);
-                if let Some(instruction) = instructions.pop() {
-                    match instruction {
-                        Instruction::Noop => {
-                            // println!("noop");
-                        }
-                        Instruction::Addx(value) => {
-                            // println!("addx {}", value);
-                            pending_instruction = Some((i + 1, instruction.clone()));
-                        }
-                    }
-                } else {
-                    // println!("no instructions left!");
-                }
-                // println!("During cycle {}", i);
-                callback(Stage::During(i, self.register));
-                // println!("End of cycle {}: finish executing", i);
-            } else if let Some((when, instruction)) = pending_instruction {
-                // println!("During cycle {}", i);
-                callback(Stage::During(i, self.register));
-                // print!("End of cycle {}: finish executing ", i);
-                if i == when {
-                    match instruction {
-                        Instruction::Noop => {
-                            // println!("Should never get here!");
-                        }
-                        Instruction::Addx(value) => {
-                            self.register += value;
-                            // println!("addx {} (Register X is now {}", value, self.register);
-                        }
-                    }
-                    pending_instruction = None;
-                } else {
-                    // println!("noop");
-                }
-            } else {
-                // println!("{:?}: Outta instructions", i);
-                break;
-            }
-            callback(Stage::EndOfCycle(i, self.register));
+impl<P> Iterator for CPU<P>
+where
+    P: Iterator,
+    P::Item: Borrow<Instruction>,
+{
+    type Item = Stage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(stage) = self.retired.take() {
+            return Some(stage);
         }
+
+        if self.current.is_none() {
+            self.current = Some(self.program.next()?);
+        }
+
+        let instruction = self.current.as_ref()?.borrow();
+        self.cycle += 1;
+        self.elapsed += 1;
+
+        let during = Stage::During {
+            cycle: self.cycle,
+            pc: self.pc,
+            registers: self.registers,
+        };
+
+        if self.elapsed == instruction.op.cycles {
+            (instruction.op.execute)(&mut self.registers, &instruction.operands);
+            self.retired = Some(Stage::Retired {
+                cycle: self.cycle,
+                pc: self.pc,
+                registers: self.registers,
+            });
+            self.pc += 1;
+            self.elapsed = 0;
+            self.current = None;
+        }
+
+        Some(during)
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 struct CRT {
     width: usize,
     height: usize,
-    display: Vec<u8>,
+    display: Vec<bool>,
     position: i64,
 }
 
@@ -198,33 +336,133 @@ impl CRT {
 
     fn plot(&mut self, step: i64) {
         if step >= (self.width * self.height) as i64 {
-            println!("Returning earlier");
             return;
         }
 
         let step = step % self.width as i64;
-        if self.cursor_on_position(step) {
-            // println!("Drawing #: {:?}", self.position);
-            self.display.push(b'#');
-        } else {
-            // println!("Drawing .: {:?}", self.position);
-            self.display.push(b'.');
-        }
+        self.display.push(self.cursor_on_position(step));
     }
 
-    fn print(&self) {
-        for c in self.display.iter().map(|c| *c as char).enumerate() {
-            if c.0 % self.width == 0 && c.0 != 0 {
-                println!("");
-            }
-            print!("{}", c.1);
+    /// The lit pixels, one row per scan line. Fails when the program ended
+    /// before every pixel was drawn.
+    fn image(&self) -> Result<Grid<bool>> {
+        let pixels = self.width * self.height;
+        if self.display.len() < pixels {
+            return Err(Errors::InvalidState(format!(
+                "the program ended after {} cycles, drawing the screen takes {}",
+                self.display.len(),
+                pixels
+            ))
+            .into());
         }
+
+        Grid::from_rows(
+            self.display
+                .chunks(self.width)
+                .map(<[bool]>::to_vec)
+                .collect(),
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CRT;
+    use aoc_core::{parse, Solution, StreamingSolution};
+
+    use super::{Day10, InstructionSet, Op, Register, Stage, CPU, CRT};
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day10::parse("noop\naddx 3\naddx x").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 3, column 6: expected a number`"
+        );
+
+        let err = Day10::parse("noop\nmul 2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2, column 1: unknown instruction `mul``"
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let program = Day10::parse("noop\naddx 3\naddx -5").unwrap();
+        let x = |stage: &Stage| match stage {
+            Stage::During { registers, .. } | Stage::Retired { registers, .. } => {
+                registers[Register::X]
+            }
+        };
+
+        let during: Vec<_> = CPU::new(&program)
+            .filter(|stage| matches!(stage, Stage::During { .. }))
+            .map(|stage| x(&stage))
+            .collect();
+        assert_eq!(during, vec![1, 1, 1, 4, 4]);
+
+        let retired: Vec<_> = CPU::new(&program)
+            .filter_map(|stage| match stage {
+                Stage::Retired { cycle, pc, .. } => Some((cycle, pc, x(&stage))),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(retired, vec![(1, 0, 1), (3, 1, 4), (5, 2, -1)]);
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        let set = InstructionSet::default().with(Op {
+            name: "mulx",
+            operands: 1,
+            cycles: 3,
+            execute: |registers, operands| registers[Register::X] *= operands[0],
+        });
+        let program = vec![
+            parse::all("addx 2", |i| set.parse(i)).unwrap(),
+            parse::all("mulx 5", |i| set.parse(i)).unwrap(),
+        ];
+
+        let mut cpu = CPU::new(&program);
+        let cycles = cpu
+            .by_ref()
+            .filter(|stage| matches!(stage, Stage::During { .. }))
+            .count();
+        assert_eq!(cycles, 5);
+        assert_eq!(cpu.registers()[Register::X], 15);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input = "addx 1\nnoop\n".repeat(80);
+        let program = Day10::parse(&input).unwrap();
+
+        assert_eq!(
+            Day10::part1_stream(&mut input.as_bytes()).unwrap(),
+            Day10::part1(&program).unwrap()
+        );
+        assert_eq!(
+            Day10::part2_stream(&mut input.as_bytes()).unwrap(),
+            Day10::part2(&program).unwrap()
+        );
+
+        let err = Day10::part1_stream(&mut "noop\naddx\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2, column 5: expected ` ``"
+        );
+    }
+
+    #[test]
+    fn test_short_program() {
+        let program = Day10::parse("noop\naddx 3\naddx -5").unwrap();
+
+        let err = Day10::part2(&program).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid State: `the program ended after 5 cycles, drawing the screen takes 240`"
+        );
+    }
 
     #[test]
     fn test_cur_position() {
@@ -1,27 +1,40 @@
 use std::{
     cell::RefCell,
     collections::{HashMap, VecDeque},
-    fs,
     rc::Rc,
 };
 
 use anyhow::Result;
 
-use crate::Errors;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day11.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
-}
+pub struct Day11;
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let monkeys: Vec<Monkey> = input
-        .split("\n\n")
-        .filter_map(|c| Monkey::try_from(c).ok())
-        .collect();
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let monkeys = parse::blocks(input, Monkey::parse).map_err(Errors::from)?;
+        check_targets(&monkeys)?;
+
+        Ok(monkeys)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
 
+fn solve_part_1(monkeys: &[Monkey]) -> Result<usize> {
     let monkeys: Vec<Rc<RefCell<Monkey>>> = monkeys
         .iter()
         .map(|m| Rc::new(RefCell::new(m.clone())))
@@ -35,19 +48,15 @@ fn solve_part_1(input: &str) -> Result<()> {
     let mut inspect_count = HashMap::new();
 
     for _ in 0..20 {
-        play_round(&monkeys, &mut monkey_mapping, &mut inspect_count, 3);
+        play_round(&monkeys, &mut monkey_mapping, &mut inspect_count, |item| {
+            item / 3
+        });
     }
 
-    println!("Day 11-1: {:?}", inspect_count);
-    Ok(())
+    Ok(monkey_business(&inspect_count))
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let monkeys: Vec<Monkey> = input
-        .split("\n\n")
-        .filter_map(|c| Monkey::try_from(c).ok())
-        .collect();
-
+fn solve_part_2(monkeys: &[Monkey]) -> Result<usize> {
     let monkeys: Vec<Rc<RefCell<Monkey>>> = monkeys
         .iter()
         .map(|m| Rc::new(RefCell::new(m.clone())))
@@ -62,18 +71,48 @@ fn solve_part_2(input: &str) -> Result<()> {
     let relief = monkeys
         .iter()
         .map(|m| m.borrow().divisor())
-        .fold(1, |acc, i| acc * i);
+        .product::<usize>();
 
     for _ in 0..10000 {
-        play_round(&monkeys, &mut monkey_mapping, &mut inspect_count, relief);
+        play_round(&monkeys, &mut monkey_mapping, &mut inspect_count, |item| {
+            item % relief
+        });
+    }
+
+    Ok(monkey_business(&inspect_count))
+}
+
+/// Every monkey has to throw to another monkey that exists, or items would
+/// be lost or thrown back onto the pile being worked through.
+fn check_targets(monkeys: &[Monkey]) -> Result<(), Errors> {
+    for monkey in monkeys {
+        for target in [monkey.test.true_case, monkey.test.false_case] {
+            let target = target.to_string();
+
+            if target == monkey.name {
+                let message = format!("monkey {} throws to itself", monkey.name);
+                return Err(Errors::InvalidState(message));
+            }
+
+            if !monkeys.iter().any(|m| m.name == target) {
+                let message = format!("monkey {} throws to missing monkey {}", monkey.name, target);
+                return Err(Errors::InvalidState(message));
+            }
+        }
     }
 
-    println!("Day 11-2: {:?}", inspect_count);
     Ok(())
 }
 
+fn monkey_business(inspect_count: &HashMap<String, usize>) -> usize {
+    let mut counts: Vec<usize> = inspect_count.values().copied().collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+
+    counts.iter().take(2).product()
+}
+
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     name: String,
     items: VecDeque<usize>,
     operation: Operation,
@@ -94,37 +133,35 @@ enum Operation {
     Squared,
 }
 
-const NAME_INDEX: usize = 0;
-const ITEMS_INDEX: usize = 1;
-const OPERATION_INDEX: usize = 2;
-const TEST_INDEX: usize = 3;
-
 impl TryFrom<&str> for Operation {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<&str> = value.trim().split(' ').collect();
-
-        let value = if split[5] == "old" {
-            return Ok(Operation::Squared);
-        } else {
-            split[5].parse()?
-        };
-        match split[4] {
-            "*" => Ok(Operation::Multiply(value)),
-            "+" => {
-                if split[5] == "old" {
-                    Ok(Operation::Multiply(value))
-                } else {
-                    Ok(Operation::Add(value))
-                }
-            }
-            _ => Err(Errors::ParseError("Operation parse error".into())),
-        }
+        Ok(parse::all(value, Operation::parse)?)
     }
 }
 
 impl Operation {
+    /// The right hand side of an operation, like `new = old * 19`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        input.tag("new = old ")?;
+        let operator = input.one_of(&["*", "+"])?;
+        input.tag(" ")?;
+
+        if input.attempt(|i| i.tag("old")).is_some() {
+            return Ok(match operator {
+                "*" => Operation::Squared,
+                _ => Operation::Multiply(2),
+            });
+        }
+
+        let value = input.unsigned()?;
+        Ok(match operator {
+            "*" => Operation::Multiply(value),
+            _ => Operation::Add(value),
+        })
+    }
+
     fn act(&self, value: usize) -> usize {
         match self {
             Self::Add(i) => value + i,
@@ -134,29 +171,22 @@ impl Operation {
     }
 }
 
-impl TryFrom<Vec<String>> for Test {
-    type Error = Errors;
+impl Test {
+    /// The divisibility test and the two monkeys it throws to.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let divisible = input.key_value("Test", |i| {
+            i.tag("divisible by ")?;
+            let start = *i;
+            let divisor = i.unsigned()?;
 
-    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
-        if value.len() != 3 {
-            return Err(Errors::ParseError("Invalid lines for test".into()));
-        }
+            if divisor == 0 {
+                return Err(start.error("nothing is divisible by 0"));
+            }
 
-        let divisible = value[0]
-            .split(' ')
-            .last()
-            .ok_or(Errors::ParseError("Invalid divible".into()))?
-            .parse::<usize>()?;
-        let true_case = value[1]
-            .split(' ')
-            .last()
-            .ok_or(Errors::ParseError("Invalid True case".into()))?
-            .parse::<usize>()?;
-        let false_case = value[2]
-            .split(' ')
-            .last()
-            .ok_or(Errors::ParseError("Invalid False case".into()))?
-            .parse::<usize>()?;
+            Ok(divisor)
+        })?;
+        let true_case = input.key_value("If true", Test::parse_target)?;
+        let false_case = input.key_value("If false", Test::parse_target)?;
 
         Ok(Test {
             divisible,
@@ -164,59 +194,68 @@ impl TryFrom<Vec<String>> for Test {
             false_case,
         })
     }
+
+    fn parse_target(input: &mut Input) -> Result<usize, ParseError> {
+        input.tag("throw to monkey ")?;
+        input.unsigned()
+    }
 }
 
 impl TryFrom<&str> for Monkey {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<&str> = value.split('\n').collect();
-
-        if split.len() != 6 {
-            return Err(Errors::ParseError("Invalid Monkey".into()));
-        }
-
-        let name = split[NAME_INDEX].split(' ').collect::<Vec<&str>>()[1]
-            .trim_end_matches(':')
-            .to_string();
-
-        let items = split[ITEMS_INDEX].split(':').collect::<Vec<&str>>()[1]
-            .split(',')
-            .filter_map(|n| n.trim().parse::<usize>().ok())
-            .collect::<VecDeque<usize>>();
-
-        let operation = Operation::try_from(split[OPERATION_INDEX])?;
+        Ok(parse::all(value, Monkey::parse)?)
+    }
+}
 
-        let test = &split[TEST_INDEX..];
-        let test: Vec<String> = test.iter().map(|c| c.to_string()).collect();
-        let test = Test::try_from(test)?;
+impl Monkey {
+    /// One block of the input, from `Monkey 0:` to its `If false` line.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let name = input.line(|i| {
+            i.tag("Monkey ")?;
+            let name = i.take_while1("a monkey number", |c| c.is_ascii_digit())?;
+            i.tag(":")?;
+
+            Ok(name.to_string())
+        })?;
+
+        let items = input.key_value("Starting items", |i| {
+            i.separated(", ", |i| i.unsigned::<usize>())
+        })?;
+        let operation = input.key_value("Operation", Operation::parse)?;
+        let test = Test::parse(input)?;
 
         Ok(Monkey {
             name,
-            items,
+            items: items.into(),
             operation,
             test,
         })
     }
 }
 
-fn play_round(
+fn play_round<F>(
     monkeys: &Vec<Rc<RefCell<Monkey>>>,
     mapping: &mut HashMap<String, Rc<RefCell<Monkey>>>,
     inspect_count: &mut HashMap<String, usize>,
-    relief: usize,
-) {
+    relief: F,
+) where
+    F: Fn(usize) -> usize,
+{
     for monkey in monkeys {
-        play_monkey(Rc::clone(&monkey), mapping, inspect_count, relief);
+        play_monkey(Rc::clone(monkey), mapping, inspect_count, &relief);
     }
 }
 
-fn play_monkey(
+fn play_monkey<F>(
     monkey: Rc<RefCell<Monkey>>,
     mapping: &mut HashMap<String, Rc<RefCell<Monkey>>>,
     inspect_count: &mut HashMap<String, usize>,
-    relief: usize,
-) {
+    relief: &F,
+) where
+    F: Fn(usize) -> usize,
+{
     let operation = monkey.borrow().operation;
     let true_case = format!("{}", monkey.borrow().test.true_case);
     let false_case = format!("{}", monkey.borrow().test.false_case);
@@ -234,13 +273,13 @@ fn play_monkey(
         // println!("Monkey inspects an item with worry level of {}", item);
         let item = operation.act(item);
         // println!("Worry level is {:?} to {}", operation, item);
-        let item = item % relief;
+        let item = relief(item);
         // println!(
         //     "Monkey gets bored with item. Worry level is divided by 3 to {}",
         //     item
         // );
 
-        let toss_to = if item % divisible == 0 {
+        let toss_to = if item.is_multiple_of(divisible) {
             // println!("Current worry level is divisible by {}", divisible);
             true_case.clone()
         } else {
@@ -263,3 +302,73 @@ impl Monkey {
         self.test.divisible
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc_core::Solution;
+
+    use super::{Day11, Monkey, Operation};
+
+    #[test]
+    fn test_parse_monkey() {
+        let monkey = Monkey::try_from(
+            "Monkey 2:\n  \
+             Starting items: 79, 60, 97\n  \
+             Operation: new = old + old\n  \
+             Test: divisible by 13\n    \
+             If true: throw to monkey 1\n    \
+             If false: throw to monkey 3",
+        )
+        .unwrap();
+
+        assert_eq!(monkey.name, "2");
+        assert_eq!(monkey.items, [79, 60, 97]);
+        assert_eq!(monkey.operation.act(5), 10);
+        assert_eq!(
+            (
+                monkey.test.divisible,
+                monkey.test.true_case,
+                monkey.test.false_case
+            ),
+            (13, 1, 3)
+        );
+
+        assert_eq!(Operation::try_from("new = old * old").unwrap().act(7), 49);
+        assert_eq!(Operation::try_from("new = old + 3").unwrap().act(7), 10);
+        assert!(Operation::try_from("new = old - 3").is_err());
+    }
+
+    #[test]
+    fn test_bad_monkeys() {
+        let monkey = |name: usize, divisor: usize, targets: (usize, usize)| {
+            format!(
+                "Monkey {}:\n  \
+                 Starting items: 79\n  \
+                 Operation: new = old + 1\n  \
+                 Test: divisible by {}\n    \
+                 If true: throw to monkey {}\n    \
+                 If false: throw to monkey {}\n",
+                name, divisor, targets.0, targets.1
+            )
+        };
+
+        let errors = [
+            (
+                monkey(0, 0, (1, 1)) + "\n" + &monkey(1, 2, (0, 0)),
+                "Parsing Error: `line 4, column 22: nothing is divisible by 0`",
+            ),
+            (
+                monkey(0, 2, (0, 1)) + "\n" + &monkey(1, 2, (0, 0)),
+                "Invalid State: `monkey 0 throws to itself`",
+            ),
+            (
+                monkey(0, 2, (1, 1)) + "\n" + &monkey(1, 2, (0, 7)),
+                "Invalid State: `monkey 1 throws to missing monkey 7`",
+            ),
+        ];
+
+        for (input, message) in errors {
+            assert_eq!(Day11::parse(&input).unwrap_err().to_string(), message);
+        }
+    }
+}
@@ -1,134 +1,97 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs,
-};
-
 use anyhow::Result;
 
-use crate::Errors;
+use aoc_core::{graph, Errors, Grid, Solution};
+
+use crate::Coord;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Puzzle;
+    type Part1 = usize;
+    type Part2 = usize;
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day12.txt")?;
+    fn parse(input: &str) -> Result<Self::Input> {
+        Puzzle::try_from(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
 
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-struct Puzzle {
-    start: Vec<Coord>,
+pub struct Puzzle {
+    start: Coord,
     end: Coord,
-    maze: Vec<Vec<u8>>,
+    maze: Grid<u8>,
 }
 
 impl TryFrom<&str> for Puzzle {
-    type Error = Errors;
+    type Error = anyhow::Error;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let maze: Vec<Vec<u8>> = value
-            .split("\n")
-            .map(|l| l.as_bytes().iter().map(|c| *c).collect())
-            .collect();
-
-        let mut start = vec![];
-        let mut end = None;
-
-        for y in 0..maze.len() {
-            for x in 0..maze[y].len() {
-                match maze[y][x] {
-                    b'S' | b'a' => start.push(Coord::new(x as i64, y as i64)),
-                    b'E' => end = Option::Some(Coord::new(x as i64, y as i64)),
-                    _ => {}
-                }
-            }
-        }
+        let maze = Grid::parse(value, |c| Ok(c as u8))?;
+
+        let start = maze
+            .position(|c| *c == b'S')
+            .and_then(|p| p.try_cast())
+            .ok_or(Errors::MissingInput("no start `S` in the map".into()))?;
 
-        let end = end.ok_or(Errors::ParseError("Could not find end".into()))?;
+        let end = maze
+            .position(|c| *c == b'E')
+            .and_then(|p| p.try_cast())
+            .ok_or(Errors::MissingInput("no end `E` in the map".into()))?;
 
         Ok(Puzzle { start, end, maze })
     }
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let puzzle = Puzzle::try_from(input)?;
-
-    println!("Day 12-1: {:?}", puzzle.shortest_distance(puzzle.start[0]));
-    Ok(())
+fn solve_part_1(puzzle: &Puzzle) -> Result<usize> {
+    let path = graph::bfs_to(
+        [puzzle.start],
+        |cur| {
+            cur.neighbours4()
+                .filter(|next| puzzle.can_move(*cur, *next))
+                .collect::<Vec<_>>()
+        },
+        |cur| *cur == puzzle.end,
+    )
+    .ok_or(Errors::InvalidState("No path to the end".into()))?;
+
+    Ok(path.cost)
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let puzzle = Puzzle::try_from(input)?;
-
-    let distances = puzzle
-        .start
-        .iter()
-        .filter_map(|s| puzzle.shortest_distance(*s))
-        .min();
-
-    println!("Day 12-2: {:?}", distances.unwrap());
-    Ok(())
+/// Walks backwards from the end, so a single search finds the closest of all
+/// the possible starting points.
+fn solve_part_2(puzzle: &Puzzle) -> Result<usize> {
+    let path = graph::bfs_to(
+        [puzzle.end],
+        |cur| {
+            cur.neighbours4()
+                .filter(|prev| puzzle.can_move(*prev, *cur))
+                .collect::<Vec<_>>()
+        },
+        |cur| matches!(puzzle.at(*cur), Some(b'S' | b'a')),
+    )
+    .ok_or(Errors::InvalidState("No path to the end".into()))?;
+
+    Ok(path.cost)
 }
 
 impl Puzzle {
-    fn shortest_distance(&self, start: Coord) -> Option<usize> {
-        let mut queue = VecDeque::new();
-        let mut steps = 0;
-
-        let mut visited = HashSet::new();
-
-        queue.push_back(start);
-
-        while !queue.is_empty() {
-            let to_deplete = queue.len();
-
-            for _ in 0..to_deplete {
-                let cur = queue.pop_front().unwrap();
-
-                if !self.within(cur) || visited.contains(&cur) {
-                    continue;
-                }
-
-                if self.maze[cur.y()][cur.x()] == b'E' {
-                    return Some(steps);
-                }
-
-                visited.insert(cur);
-
-                if self.can_move(cur, Coord::new(cur.x - 1, cur.y)) {
-                    queue.push_back(Coord::new(cur.x - 1, cur.y));
-                }
-                if self.can_move(cur, Coord::new(cur.x + 1, cur.y)) {
-                    queue.push_back(Coord::new(cur.x + 1, cur.y));
-                }
-                if self.can_move(cur, Coord::new(cur.x, cur.y - 1)) {
-                    queue.push_back(Coord::new(cur.x, cur.y - 1));
-                }
-                if self.can_move(cur, Coord::new(cur.x, cur.y + 1)) {
-                    queue.push_back(Coord::new(cur.x, cur.y + 1));
-                }
-            }
-
-            steps += 1;
-        }
-
-        None
-    }
-
-    fn within(&self, coord: Coord) -> bool {
-        coord.y >= 0
-            && coord.y() < self.maze.len()
-            && coord.x >= 0
-            && coord.x() < self.maze[coord.y()].len()
+    fn at(&self, coord: Coord) -> Option<u8> {
+        self.maze.get(coord).copied()
     }
 
     fn can_move(&self, cur_position: Coord, intention: Coord) -> bool {
-        if !self.within(intention) {
+        let (Some(intent_c), Some(cur_c)) = (self.at(intention), self.at(cur_position)) else {
             return false;
         };
 
-        let intent_c = self.maze[intention.y()][intention.x()];
-        let cur_c = self.maze[cur_position.y()][cur_position.x()];
-
         if intent_c == b'S' {
             return false;
         }
@@ -149,22 +112,19 @@ impl Puzzle {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord {
-    x: i64,
-    y: i64,
-}
+#[cfg(test)]
+mod tests {
+    use aoc_core::Solution;
 
-impl Coord {
-    fn new(x: i64, y: i64) -> Self {
-        Coord { x, y }
-    }
+    use super::Day12;
 
-    fn y(&self) -> usize {
-        self.y as usize
-    }
+    const EXAMPLE: &str = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi";
+
+    #[test]
+    fn test_example() {
+        let puzzle = Day12::parse(EXAMPLE).unwrap();
 
-    fn x(&self) -> usize {
-        self.x as usize
+        assert_eq!(Day12::part1(&puzzle).unwrap(), 31);
+        assert_eq!(Day12::part2(&puzzle).unwrap(), 29);
     }
 }
@@ -1,42 +1,47 @@
-use std::fs;
+use std::fmt::Display;
 
 use anyhow::Result;
 
-use crate::Errors;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day13.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
-}
+pub struct Day13;
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let comparisons: Vec<Comparison> = input
-        .split("\n\n")
-        .filter_map(|ls| Comparison::try_from(ls).ok())
-        .collect();
+impl Solution for Day13 {
+    type Input = Vec<Comparison>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::blocks(input, Comparison::parse).map_err(Errors::from)?)
+    }
 
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(comparisons: &[Comparison]) -> Result<usize> {
     let valid: usize = comparisons
         .iter()
         .enumerate()
-        .filter(|(idx, c)| c.left <= c.right)
+        .filter(|(_idx, c)| c.left <= c.right)
         .map(|(idx, _)| idx + 1)
         .sum();
 
-    println!("Day 13-1: {:?}", valid);
-    Ok(())
+    Ok(valid)
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let packets: Vec<&str> = input
-        .split("\n\n")
-        .flat_map(|c| c.split('\n').collect::<Vec<&str>>())
-        .collect();
-
-    let mut packets: Vec<Packet> = packets
+fn solve_part_2(comparisons: &[Comparison]) -> Result<usize> {
+    let mut packets: Vec<Packet> = comparisons
         .iter()
-        .filter_map(|s| Packet::try_from(*s).ok())
+        .flat_map(|c| [c.left.clone(), c.right.clone()])
         .collect();
 
     let first_divider = Packet::divider(2);
@@ -50,12 +55,11 @@ fn solve_part_2(input: &str) -> Result<()> {
     let first_index = packets.binary_search(&first_divider).unwrap();
     let second_index = packets.binary_search(&second_divider).unwrap();
 
-    println!("Day 13-2: {:?}", (first_index + 1) * (second_index + 1));
-    Ok(())
+    Ok((first_index + 1) * (second_index + 1))
 }
 
 #[derive(Debug)]
-struct Comparison {
+pub struct Comparison {
     left: Packet,
     right: Packet,
 }
@@ -64,14 +68,15 @@ impl TryFrom<&str> for Comparison {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<&str> = value.split('\n').collect();
-
-        if split.len() != 2 {
-            return Err(Errors::ParseError("Invalid comparison".into()));
-        }
+        Ok(parse::all(value, Comparison::parse)?)
+    }
+}
 
-        let left = Packet::try_from(split[0])?;
-        let right = Packet::try_from(split[1])?;
+impl Comparison {
+    /// A pair of packets, one per line.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let left = input.line(Packet::parse)?;
+        let right = input.line(Packet::parse)?;
 
         Ok(Comparison { left, right })
     }
@@ -88,34 +93,33 @@ impl Packet {
         Packet::Value(value)
     }
 
-    fn new_list() -> Self {
-        Packet::List(vec![])
-    }
-
     fn divider(value: usize) -> Self {
         Packet::List(vec![Packet::List(vec![Packet::Value(value)])])
     }
 
-    fn insert_list(&mut self, other: Packet) {
-        match self {
-            Self::Value(_) => {}
-            Self::List(list) => list.push(other),
+    /// A number, or a bracketed list of packets split by commas.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        if input.attempt(|i| i.tag("[")).is_none() {
+            return Ok(Packet::new(input.unsigned()?));
         }
-    }
 
-    fn insert(&mut self, value: usize) {
-        match self {
-            Self::Value(_) => {}
-            Self::List(list) => list.push(Self::new(value)),
+        if input.attempt(|i| i.tag("]")).is_some() {
+            return Ok(Packet::List(vec![]));
         }
+
+        let list = input.separated(",", Packet::parse)?;
+        input.tag("]")?;
+
+        Ok(Packet::List(list))
     }
 }
 
-impl ToString for Packet {
-    fn to_string(&self) -> String {
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Value(v) => format!("{}", *v),
-            Self::List(l) => format!(
+            Self::Value(v) => write!(f, "{}", *v),
+            Self::List(l) => write!(
+                f,
                 "[{}]",
                 l.iter()
                     .map(Packet::to_string)
@@ -130,37 +134,7 @@ impl TryFrom<&str> for Packet {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut stack = vec![];
-
-        let value = value.as_bytes();
-        let mut detect_value = None;
-        for i in 0..value.len() {
-            match value[i] {
-                b'[' => stack.push(Self::new_list()),
-                b']' | b',' => {
-                    // Check if there is a value, insert into array
-                    if let Some(idx) = detect_value {
-                        let v = std::str::from_utf8(&value[idx..i])?.parse::<usize>()?;
-                        stack.last_mut().unwrap().insert(v);
-                    }
-
-                    detect_value = None;
-                    if value[i] == b']' {
-                        if stack.len() > 1 {
-                            let l = stack.pop().unwrap();
-                            stack.last_mut().unwrap().insert_list(l);
-                        }
-                    }
-                }
-                _ => {
-                    if detect_value.is_none() {
-                        detect_value = Some(i);
-                    }
-                }
-            }
-        }
-
-        Ok(stack[0].clone())
+        Ok(parse::all(value, Packet::parse)?)
     }
 }
 
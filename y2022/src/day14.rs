@@ -1,96 +1,94 @@
-use std::{cmp::max, fs};
+use std::{cmp::max, fmt::Display};
 
 use anyhow::Result;
 
-use crate::{Coord, Errors};
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution, SparseGrid,
+};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day14.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
-}
+use crate::Coord;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Cave;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines: Vec<Line> = parse::lines(input, Coords::parse)
+            .map_err(Errors::from)?
+            .into_iter()
+            .flat_map(map_coords_to_lines)
+            .collect();
+
+        Ok(Cave::try_from(lines)?)
+    }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let lines: Vec<Line> = input
-        .split('\n')
-        .filter_map(|l| Coords::try_from(l).ok())
-        .flat_map(map_coords_to_lines)
-        .collect();
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
 
-    let mut cave = Cave::from(lines);
+fn solve_part_1(cave: &Cave) -> Result<usize> {
+    let mut cave = cave.clone();
 
     let mut results = 0;
     while cave.drop_sand(Coord::new(499, 0)) {
         results += 1;
     }
 
-    println!("Day 14-1: {}", results);
-    Ok(())
+    Ok(results)
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let lines: Vec<Line> = input
-        .split('\n')
-        .filter_map(|l| Coords::try_from(l).ok())
-        .flat_map(map_coords_to_lines)
-        .collect();
+fn solve_part_2(cave: &Cave) -> Result<usize> {
+    let mut cave = cave.clone();
 
-    let mut cave = Cave::from(lines);
-
-    cave.with_buffer(500);
+    cave.with_floor();
 
     let mut results = 1;
-    while cave.drop_sand(Coord::new(999, 0)) {
+    while cave.drop_sand(Coord::new(499, 0)) {
         results += 1;
     }
 
-    println!("Day 14-2: {:?}", results);
-    Ok(())
+    Ok(results)
 }
 
-struct Cave {
-    map: Vec<Vec<Unit>>,
+#[derive(Debug, Clone)]
+pub struct Cave {
+    map: SparseGrid<Unit>,
+    lowest_rock: isize,
+    floor: Option<isize>,
 }
 
-impl From<Vec<Line>> for Cave {
-    fn from(lines: Vec<Line>) -> Self {
-        let max_x = lines
-            .iter()
-            .fold(isize::MIN, |prev, line| max(prev, line.max_x()))
-            + 1;
-        let max_y = lines
-            .iter()
-            .fold(isize::MIN, |prev, line| max(prev, line.max_y()))
-            + 1;
-
-        let mut map = vec![vec![Unit::Air; max_x as usize]; max_y as usize];
-
-        for line in lines.iter() {
-            for coord in line.iter() {
-                map[coord.y as usize][coord.x as usize] = Unit::Rock;
-            }
-        }
-
-        Cave { map }
-    }
-}
-
-impl TryFrom<&str> for Coord {
+impl TryFrom<Vec<Line>> for Cave {
     type Error = Errors;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let value: Vec<isize> = value
-            .trim()
-            .split(',')
-            .map(|s| s.parse().unwrap())
-            .collect();
+    /// Fails without any rock, as there would be nothing for sand to land
+    /// on and no floor to put under it.
+    fn try_from(lines: Vec<Line>) -> Result<Self, Self::Error> {
+        let lowest_rock = lines
+            .iter()
+            .map(Line::max_y)
+            .max()
+            .ok_or_else(|| Errors::MissingInput("no rock in the cave".to_string()))?;
 
-        if value.len() != 2 {
-            return Err(Errors::ParseError("Issues parsing coords".into()));
-        }
+        let map = lines
+            .iter()
+            .flat_map(Line::iter)
+            .map(|coord| (coord, Unit::Rock))
+            .collect();
 
-        Ok(Coord::new(value[0] - 1, value[1]))
+        Ok(Cave {
+            map,
+            lowest_rock,
+            floor: None,
+        })
     }
 }
 
@@ -100,12 +98,19 @@ impl TryFrom<&str> for Coords {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let coords: Vec<Coord> = value
-            .split("->")
-            .filter_map(|c| Coord::try_from(c).ok())
-            .collect();
+        Ok(parse::all(value, Coords::parse)?)
+    }
+}
+
+impl Coords {
+    /// A rock path like `498,4 -> 498,6 -> 496,6`, shifted one column left
+    /// so sand falls from `499,0`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let coords = input.separated(" -> ", |i| i.point(","))?;
 
-        Ok(Self(coords))
+        Ok(Coords(
+            coords.into_iter().map(|c| c + Coord::new(-1, 0)).collect(),
+        ))
     }
 }
 
@@ -125,19 +130,18 @@ impl Iterator for LineIterator {
     type Item = Coord;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.direction == Coord::new(0, 0) {
+        if self.direction == Coord::ORIGIN {
             return None;
         }
 
         if self.start == self.end {
-            self.direction = Coord::new(0, 0);
+            self.direction = Coord::ORIGIN;
             return Some(self.end);
         }
 
         let result = self.start;
 
-        self.start.x += self.direction.x;
-        self.start.y += self.direction.y;
+        self.start += self.direction;
 
         Some(result)
     }
@@ -148,14 +152,10 @@ impl Line {
         LineIterator {
             start: self.start,
             end: self.end,
-            direction: self.start.direction_towards(&self.end),
+            direction: self.start.direction_to(&self.end),
         }
     }
 
-    fn max_x(&self) -> isize {
-        max(self.start.x, self.end.x)
-    }
-
     fn max_y(&self) -> isize {
         max(self.start.y, self.end.y)
     }
@@ -173,33 +173,26 @@ fn map_coords_to_lines(coords: Coords) -> Vec<Line> {
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 enum Unit {
-    Air,
     Rock,
     Sand,
 }
 
 impl Unit {
-    fn to_char(&self) -> char {
+    fn to_char(self) -> char {
         match self {
-            Self::Air => '.',
             Self::Rock => '#',
             Self::Sand => 'o',
         }
     }
 }
 
-impl ToString for Cave {
-    fn to_string(&self) -> String {
-        let mut results = String::new();
-
-        for line in self.map.iter() {
-            for col in line.iter() {
-                results.push(col.to_char());
-            }
-            results.push('\n');
-        }
-
-        results
+impl Display for Cave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.map.display(|unit| unit.map_or('.', |u| u.to_char()))
+        )
     }
 }
 
@@ -212,7 +205,7 @@ impl Cave {
         let down_right = Coord::new(1, 1);
 
         loop {
-            let new_pos = sand.add(&down);
+            let new_pos = sand + down;
             if !self.within_map(new_pos) {
                 return false;
             }
@@ -221,7 +214,7 @@ impl Cave {
                 continue;
             }
 
-            let new_pos = sand.add(&down_left);
+            let new_pos = sand + down_left;
             if !self.within_map(new_pos) {
                 return false;
             }
@@ -230,7 +223,7 @@ impl Cave {
                 continue;
             }
 
-            let new_pos = sand.add(&down_right);
+            let new_pos = sand + down_right;
             if !self.within_map(new_pos) {
                 return false;
             }
@@ -239,7 +232,7 @@ impl Cave {
                 continue;
             }
 
-            self.map[sand.y as usize][sand.x as usize] = Unit::Sand;
+            self.map.insert(sand, Unit::Sand);
 
             break;
         }
@@ -248,46 +241,44 @@ impl Cave {
             return false;
         }
 
-        return true;
+        true
     }
 
+    /// Without a floor, anything below the lowest rock falls into the abyss.
     fn within_map(&self, coord: Coord) -> bool {
-        if coord.x < 0
-            || coord.x >= self.map[0].len() as isize
-            || coord.y >= self.map.len() as isize
-        {
-            return false;
-        }
-
-        true
+        self.floor.is_some() || coord.y <= self.lowest_rock
     }
 
     fn is_taken(&self, coord: Coord) -> bool {
-        self.map[coord.y as usize][coord.x as usize] != Unit::Air
+        self.floor == Some(coord.y) || self.map.contains(coord)
     }
 
-    fn with_buffer(&mut self, buffer: usize) {
-        let mut new_map = vec![];
-        for row in self.map.iter() {
-            let mut new_row = vec![];
-            for _ in 0..buffer {
-                new_row.push(Unit::Air);
-            }
-            for u in row.iter() {
-                new_row.push(*u);
-            }
-            for _ in 0..buffer {
-                new_row.push(Unit::Air);
-            }
-            new_map.push(new_row);
-        }
+    /// Adds an endless floor two rows below the lowest rock.
+    fn with_floor(&mut self) {
+        self.floor = Some(self.lowest_rock + 2);
+    }
+}
 
-        let empty_row = vec![Unit::Air; new_map[0].len()];
-        let ground_row = vec![Unit::Rock; new_map[0].len()];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        new_map.push(empty_row);
-        new_map.push(ground_row);
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9\n";
 
-        self.map = new_map;
+    #[test]
+    fn test_example() {
+        let cave = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part_1(&cave).unwrap(), 24);
+        assert_eq!(solve_part_2(&cave).unwrap(), 93);
+    }
+
+    #[test]
+    fn test_no_rock() {
+        for input in ["", "498,4\n"] {
+            assert_eq!(
+                Day14::parse(input).unwrap_err().to_string(),
+                "Missing Input: `no rock in the cave`"
+            );
+        }
     }
 }
@@ -1,27 +1,45 @@
 use std::{
     cmp::{max, min},
-    collections::{HashMap, HashSet},
-    fs,
+    collections::HashMap,
     ops::RangeInclusive,
 };
 
 use anyhow::Result;
 
-use crate::{Coord, Errors};
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("/Users/son/workspace/rust-aoc/y2022/inputs/day15.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
-}
+use crate::Coord;
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let deployments: Vec<SensorDeployment> = input
-        .split('\n')
-        .filter_map(|s| SensorDeployment::try_from(s).ok())
-        .collect();
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<SensorDeployment>;
+    type Part1 = isize;
+    type Part2 = isize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let deployments = parse::lines(input, SensorDeployment::parse).map_err(Errors::from)?;
+
+        if deployments.is_empty() {
+            return Err(Errors::MissingInput("no sensors".into()).into());
+        }
+
+        Ok(deployments)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
 
+fn solve_part_1(deployments: &[SensorDeployment]) -> Result<isize> {
     let ranges: Vec<Option<RangeInclusive<isize>>> = deployments
         .iter()
         .map(|d| d.get_range_for_row(2000000))
@@ -35,20 +53,14 @@ fn solve_part_1(input: &str) -> Result<()> {
         .map(|r| (*r.start() - *r.end()).abs())
         .sum();
 
-    println!("Day 15-1: {:?}", number_of_spots);
-    Ok(())
+    Ok(number_of_spots)
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let deployments: Vec<SensorDeployment> = input
-        .split('\n')
-        .filter_map(|s| SensorDeployment::try_from(s).ok())
-        .collect();
-
+fn solve_part_2(deployments: &[SensorDeployment]) -> Result<isize> {
     let min_x = 0;
-    let min_y = deployments.iter().map(|d| d.sensor.y).min().unwrap();
+    let min_y = deployments.iter().map(|d| d.sensor.y).min().unwrap_or(0);
     let max_x = 4000000;
-    let max_y = deployments.iter().map(|d| d.sensor.y).max().unwrap();
+    let max_y = deployments.iter().map(|d| d.sensor.y).max().unwrap_or(0);
 
     let mut row_ranges: HashMap<isize, Vec<RangeInclusive<isize>>> = HashMap::new();
     for i in min_y..=max_y {
@@ -63,12 +75,13 @@ fn solve_part_2(input: &str) -> Result<()> {
         }
     }
 
-    let key = row_ranges.keys().last().unwrap();
-    let value = row_ranges.get(key).unwrap();
+    let (key, value) = row_ranges
+        .iter()
+        .last()
+        .ok_or(Errors::InvalidState("No gap left for the beacon".into()))?;
     let value = value[0].end() + 1;
 
-    println!("Day 15-2: {:?}", value * 4000000 + key);
-    Ok(())
+    Ok(value * 4000000 + key)
 }
 
 fn normalize_ranges(
@@ -97,13 +110,17 @@ fn merge_ranges(ranges: Vec<RangeInclusive<isize>>) -> Vec<RangeInclusive<isize>
             return start_cmp;
         }
 
-        return end_cmp;
+        end_cmp
     });
 
     let mut merged_ranges: Vec<RangeInclusive<isize>> = vec![];
     let mut idx = 0;
     let mut cur = 1;
 
+    if ranges.is_empty() {
+        return merged_ranges;
+    }
+
     merged_ranges.push(ranges[idx].clone());
     while cur < ranges.len() {
         if ranges[cur].start() > merged_ranges[idx].end() {
@@ -124,7 +141,7 @@ fn merge_ranges(ranges: Vec<RangeInclusive<isize>>) -> Vec<RangeInclusive<isize>
 }
 
 #[derive(Debug)]
-struct SensorDeployment {
+pub struct SensorDeployment {
     sensor: Coord,
     beacon: Coord,
 }
@@ -133,40 +150,31 @@ impl TryFrom<&str> for SensorDeployment {
     type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let input: Vec<Coord> = value
-            .split(':')
-            .map(|s| {
-                s.replace("Sensor at ", "")
-                    .replace("closest beacon is at", "")
-            })
-            .filter_map(|s| parse_coord(&s).ok())
-            .collect();
-
-        Ok(SensorDeployment {
-            sensor: input[0],
-            beacon: input[1],
-        })
+        Ok(parse::all(value, SensorDeployment::parse)?)
     }
 }
 
-fn parse_coord(input: &str) -> Result<Coord, Errors> {
-    let input: Vec<isize> = input
-        .trim()
-        .split(", ")
-        .flat_map(|s| s.split('=').filter_map(|n| n.parse::<isize>().ok()))
-        .collect();
+/// A position like `x=2, y=18`.
+fn parse_coord(input: &mut Input) -> Result<Coord, ParseError> {
+    input.tag("x=")?;
+    let x = input.signed()?;
+    input.tag(", y=")?;
+    let y = input.signed()?;
 
-    if input.len() != 2 {
-        return Err(Errors::ParseError("Invalid Coord, can't parse".into()));
-    }
-
-    Ok(Coord {
-        x: input[0],
-        y: input[1],
-    })
+    Ok(Coord { x, y })
 }
 
 impl SensorDeployment {
+    /// A report like `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        input.tag("Sensor at ")?;
+        let sensor = parse_coord(input)?;
+        input.tag(": closest beacon is at ")?;
+        let beacon = parse_coord(input)?;
+
+        Ok(SensorDeployment { sensor, beacon })
+    }
+
     fn get_range_for_row(&self, row: isize) -> Option<RangeInclusive<isize>> {
         let size = self.find_size_of_diamond();
 
@@ -184,16 +192,7 @@ impl SensorDeployment {
     }
 
     fn find_size_of_diamond(&self) -> isize {
-        if self.sensor.x == self.beacon.x {
-            (self.sensor.y - self.beacon.y).abs()
-        } else if self.sensor.y == self.beacon.y {
-            (self.sensor.x - self.beacon.x).abs()
-        } else {
-            let x_axis = (self.sensor.x - self.beacon.x).abs();
-            let y_axis = (self.sensor.y - self.beacon.y).abs();
-
-            y_axis + x_axis
-        }
+        self.sensor.manhattan(&self.beacon)
     }
 }
 
@@ -236,6 +235,22 @@ mod test {
         assert_eq!(deployment.get_range_for_row(3), Some(15..=17));
     }
 
+    #[test]
+    fn test_parse() {
+        let deployment =
+            SensorDeployment::try_from("Sensor at x=2, y=18: closest beacon is at x=-2, y=15")
+                .unwrap();
+
+        assert_eq!(deployment.sensor, Coord::new(2, 18));
+        assert_eq!(deployment.beacon, Coord::new(-2, 15));
+
+        let err = SensorDeployment::try_from("Sensor at x=2, y=18: beacon at x=-2, y=15");
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Parsing Error: `line 1, column 20: expected `: closest beacon is at ``"
+        );
+    }
+
     #[test]
     fn test_weird_bug_for_range() {
         let deployment = SensorDeployment {
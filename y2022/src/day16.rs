@@ -1,135 +1,147 @@
 use std::{
-    cmp::{max, Ordering},
-    collections::{HashMap, HashSet, VecDeque},
-    fs,
-    hash::Hash,
-    time::Instant,
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+    str::FromStr,
 };
 
 use anyhow::Result;
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day16.txt")?;
-    let example_input = fs::read_to_string("./inputs/day16_example.txt")?;
-    solve_part_1(&example_input)?;
-    // solve_part_1(&input)?;
-    let start = Instant::now();
-    solve_part_2(&example_input)?;
-    println!("Time Elapsed: {:?}", start.elapsed().as_micros());
-    let start = Instant::now();
-    solve_part_2(&input)?;
-    println!("Time Elapsed: {:?}", start.elapsed().as_micros());
-    Ok(())
-}
+use aoc_core::{
+    graph,
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let valves: Vec<Valve> = input.split('\n').map(|l| Valve::from(l)).collect();
-    let mut mapping: HashMap<String, Valve> = HashMap::new();
-    let mut connected_mapping: HashMap<&str, Vec<String>> = HashMap::new();
-    let mut memorized_mapping: HashMap<String, Vec<String>> = HashMap::new();
-
-    valves.iter().for_each(|v| {
-        connected_mapping.insert(&v.name, v.connected.clone());
-        mapping.insert(v.name.clone(), v.clone());
-    });
-
-    const MAX_TIME: usize = 30;
-    let to_open: HashSet<&str> = valves
-        .iter()
-        .filter(|v| v.flow != 0)
-        .map(|v| v.name.as_str())
-        .collect();
+pub struct Day16;
 
-    let mut check_queue: Vec<PathState> = vec![PathState {
-        path: vec![],
-        to_open,
-        score_so_far: 0,
-    }];
+impl Solution for Day16 {
+    type Input = Vec<Valve>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let mut max_score = 0;
+    fn parse(input: &str) -> Result<Self::Input> {
+        let valves = parse::lines(input, Valve::parse).map_err(Errors::from)?;
 
-    let initial = ValveOpen(0, "AA".into());
-    while let Some(state) = check_queue.pop() {
-        let cur_position = state.path.last().unwrap_or(&initial);
+        check_tunnels(&valves)?;
 
-        if state.to_open.is_empty() || cur_position.0 >= MAX_TIME {
-            max_score = max(max_score, scoree(state.path.iter(), &mapping, MAX_TIME));
-            continue;
-        }
+        Ok(valves)
+    }
 
-        let temp = state.clone();
-        for valve_to_open in state.to_open {
-            let mut new_state = temp.clone();
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
 
-            let shortest_path = find_shortest_path(
-                &cur_position.1,
-                valve_to_open,
-                &connected_mapping,
-                &mut memorized_mapping,
-            );
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
 
-            new_state.to_open.remove(valve_to_open);
-            new_state.path.push(ValveOpen(
-                cur_position.0 + shortest_path.len(),
-                valve_to_open.to_string(),
-            ));
+fn solve_part_1(valves: &[Valve]) -> Result<usize> {
+    Ok(plan(valves, "AA", 30, 1)?.pressure)
+}
 
-            check_queue.push(new_state);
-        }
-    }
+fn solve_part_2(valves: &[Valve]) -> Result<usize> {
+    Ok(plan(valves, "AA", 26, 2)?.pressure)
+}
 
-    println!("Day 16-1: {:?}", max_score);
-    Ok(())
+/// A valve opened `minute` minutes after the start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValveOpen {
+    pub minute: usize,
+    pub valve: String,
+}
+
+/// The most pressure a team can release, with the valves each member
+/// opens in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub pressure: usize,
+    pub schedules: Vec<Vec<ValveOpen>>,
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let valves: Vec<Valve> = input.split('\n').map(|l| Valve::from(l)).collect();
-    let mut mapping: HashMap<String, Valve> = HashMap::new();
-    let mut connected_mapping: HashMap<&str, Vec<String>> = HashMap::new();
-    let mut memorized_mapping: HashMap<String, Vec<String>> = HashMap::new();
-
-    valves.iter().for_each(|v| {
-        mapping.insert(v.name.clone(), v.clone());
-        connected_mapping.insert(&v.name, v.connected.clone());
-    });
-
-    const MAX_TIME: usize = 26;
-    let to_open: HashSet<&str> = valves
-        .iter()
-        .filter(|v| v.flow != 0)
-        .map(|v| v.name.as_str())
+/// Plans how `actors` working at the same time, all starting at `start`,
+/// release the most pressure in `time` minutes.
+///
+/// Actors never open the same valve, so the best team is the best split of
+/// the valves between them, each one taking its best route through its own
+/// share.
+pub fn plan(valves: &[Valve], start: &str, time: usize, actors: usize) -> Result<Plan> {
+    if actors == 0 {
+        return Err(Errors::InvalidState("A plan needs at least one actor".into()).into());
+    }
+
+    let network = Network::new(valves, start)?;
+    let single = best_within_subsets(&network.best_per_mask(time));
+
+    // teams[mask] is the best score when the actors so far share `mask`, and
+    // choices[n][mask] the share the nth actor took from it
+    let mut teams: Vec<usize> = single.iter().map(|(score, _)| *score).collect();
+    let mut choices = vec![];
+
+    let all = single.len() - 1;
+    for n in 1..actors {
+        // The whole team only ever takes every valve between them
+        let masks = if n + 1 == actors { all..=all } else { 0..=all };
+        let (best, choice) = add_actor(&single, &teams, masks);
+        teams = best;
+        choices.push(choice);
+    }
+
+    let mut remaining = all;
+    let mut shares = vec![];
+
+    for choice in choices.iter().rev() {
+        let share = choice[remaining];
+        shares.push(single[share].1);
+        remaining ^= share;
+    }
+    shares.push(single[remaining].1);
+    shares.reverse();
+
+    let schedules = shares
+        .into_iter()
+        .map(|share| network.schedule(time, share))
         .collect();
 
-    let mut all_paths = generate_all_paths(
-        to_open,
-        MAX_TIME,
-        &mapping,
-        &connected_mapping,
-        &mut memorized_mapping,
-    );
-    all_paths.sort();
-    println!("Paths: {}", all_paths.len());
-    let mut max_score = 0;
-    for person in &all_paths {
-        for elephant in &all_paths {
-            if !person.0.is_disjoint(&elephant.0) {
-                continue;
+    Ok(Plan {
+        pressure: teams[all],
+        schedules,
+    })
+}
+
+/// Best scores of `masks` once one more actor joins a team: the new actor
+/// takes some subset of each mask and the rest of the team the remainder.
+/// Returns the scores and the subset the new actor took.
+fn add_actor(
+    single: &[(usize, usize)],
+    team: &[usize],
+    masks: RangeInclusive<usize>,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut best = vec![0; team.len()];
+    let mut choice = vec![0; team.len()];
+
+    for mask in masks {
+        // Every subset of `mask`, down to the empty one
+        let mut share = mask;
+        loop {
+            let score = single[share].0 + team[mask ^ share];
+            if score > best[mask] {
+                best[mask] = score;
+                choice[mask] = share;
             }
 
-            let score = person.1 + elephant.1;
-            if score > max_score {
-                println!("S: {}", score);
-                max_score = score;
+            if share == 0 {
+                break;
             }
+            share = (share - 1) & mask;
         }
     }
 
-    println!("Day 16-2: {:?}", max_score);
-    Ok(())
+    (best, choice)
 }
 
 #[derive(Debug, Clone)]
-struct Valve {
+pub struct Valve {
     name: String,
     flow: usize,
     connected: Vec<String>,
@@ -145,304 +157,438 @@ impl Valve {
     }
 }
 
-impl From<&str> for Valve {
-    fn from(value: &str) -> Self {
-        let split: Vec<&str> = value.split("; ").collect();
-        let valve = split[0].split('=').collect::<Vec<&str>>();
-
-        let name: String = valve[0].replace("Valve ", "").replace(" has flow rate", "");
-        let flow: usize = valve[1].parse().unwrap_or(0);
-        let binding = split[1]
-            .replace("tunnels leads to valves ", "")
-            .replace("tunnels leads to valve ", "")
-            .replace("tunnels lead to valves ", "")
-            .replace("tunnels lead to valve ", "")
-            .replace("tunnel leads to valve ", "")
-            .replace("tunnel lead to valves ", "")
-            .replace("tunnel leads to valves ", "");
-        let connected: Vec<&str> = binding.split(", ").collect();
-
-        Valve::new(&name, flow, connected)
-    }
-}
+impl FromStr for Valve {
+    type Err = Errors;
 
-fn scoree<'a, I>(timings: I, mapping: &HashMap<String, Valve>, max_time: usize) -> usize
-where
-    I: Iterator<Item = &'a ValveOpen>,
-{
-    timings
-        .map(|timing| calculate_released_flow(&timing, mapping, max_time))
-        .sum::<usize>()
-}
-
-fn calculate_released_flow(
-    open_timing: &ValveOpen,
-    mapping: &HashMap<String, Valve>,
-    max_time: usize,
-) -> usize {
-    if open_timing.0 < max_time {
-        let flow = mapping.get(&open_timing.1).unwrap().flow;
-        let total_time = max_time - open_timing.0;
-
-        flow * total_time
-    } else {
-        0
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        Ok(parse::all(line, Valve::parse)?)
     }
 }
 
-#[derive(Clone)]
-struct PathState<'a> {
-    path: Vec<ValveOpen>,
-    to_open: HashSet<&'a str>,
-    score_so_far: usize,
-}
+impl Valve {
+    /// Parses `Valve AA has flow rate=0; tunnels lead to valves DD, II` in
+    /// any mix of its singular and plural wordings.
+    fn parse<'a>(input: &mut Input<'a>) -> Result<Self, ParseError> {
+        fn name<'a>(input: &mut Input<'a>) -> Result<&'a str, ParseError> {
+            input.take_while1("a valve name", |c| c.is_ascii_uppercase())
+        }
 
-impl Ord for PathState<'_> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.score_so_far.cmp(&other.score_so_far)
+        input.tag("Valve ")?;
+        let valve = name(input)?;
+        input.tag(" has flow rate=")?;
+        let flow = input.unsigned()?;
+        input.tag("; ")?;
+        input.one_of(&["tunnels ", "tunnel "])?;
+        input.one_of(&["leads ", "lead "])?;
+        input.tag("to ")?;
+        input.one_of(&["valves ", "valve "])?;
+        let connected = input.separated(", ", name)?;
+
+        Ok(Valve::new(valve, flow, connected))
     }
 }
 
-impl PartialOrd for PathState<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.score_so_far.partial_cmp(&other.score_so_far)
-    }
-}
+/// Every valve is named once and every tunnel leads to one of them.
+fn check_tunnels(valves: &[Valve]) -> Result<(), Errors> {
+    let mut names = HashSet::new();
 
-impl PartialEq<PathState<'_>> for PathState<'_> {
-    fn eq(&self, other: &PathState<'_>) -> bool {
-        self.score_so_far == other.score_so_far
+    for (i, valve) in valves.iter().enumerate() {
+        if !names.insert(valve.name.as_str()) {
+            let message = format!("valve {} is listed twice", valve.name);
+            return Err(ParseError::at_line(i + 1, message).into());
+        }
     }
-}
 
-impl Eq for PathState<'_> {}
+    for (i, valve) in valves.iter().enumerate() {
+        if let Some(unknown) = valve.connected.iter().find(|c| !names.contains(c.as_str())) {
+            let message = format!("valve {} leads to unknown valve {}", valve.name, unknown);
+            return Err(ParseError::at_line(i + 1, message).into());
+        }
+    }
 
-#[derive(Debug, Clone)]
-struct State {
-    path: Vec<String>,
-    visited: HashSet<String>,
-    time_so_far: usize,
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct StateWithTakeTime<'a> {
-    path: Vec<ValveOpen>,
-    visited: HashSet<&'a str>,
-    time_so_far: usize,
+/// Shortest walking time between every pair of valves.
+struct Tunnels<'a> {
+    index: HashMap<&'a str, usize>,
+    distances: Vec<Vec<Option<usize>>>,
 }
 
-#[derive(Debug, Clone)]
-struct PathWithScore(HashSet<ValveOpen>, usize);
+impl<'a> Tunnels<'a> {
+    fn new(valves: &'a [Valve]) -> Self {
+        let index: HashMap<&str, usize> = valves
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (v.name.as_str(), i))
+            .collect();
+
+        let edges = valves.iter().enumerate().flat_map(|(from, v)| {
+            v.connected
+                .iter()
+                .filter_map(|name| index.get(name.as_str()))
+                .map(move |to| (from, *to, 1))
+        });
+        let distances = graph::floyd_warshall(valves.len(), edges);
 
-impl Ord for PathWithScore {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.1.cmp(&self.1)
+        Tunnels { index, distances }
     }
-}
 
-impl PartialOrd for PathWithScore {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.1.partial_cmp(&self.1)
+    fn distance(&self, from: &str, to: &str) -> Option<usize> {
+        self.distances[*self.index.get(from)?][*self.index.get(to)?]
     }
 }
 
-impl PartialEq for PathWithScore {
-    fn eq(&self, other: &Self) -> bool {
-        other.1 == self.1
-    }
+/// Most valves with a flow the per-mask tables can hold.
+const MAX_VALVES: usize = 20;
+
+/// Only the valves worth opening, numbered so that a set of them fits in a
+/// bit mask, with the time it takes to walk to one and open it.
+struct Network {
+    names: Vec<String>,
+    flows: Vec<usize>,
+    /// `open_times[from][to]`, where `from` can also be the start, which is
+    /// numbered right after the valves. `None` when `to` is unreachable.
+    open_times: Vec<Vec<Option<usize>>>,
 }
 
-impl Eq for PathWithScore {}
+impl Network {
+    fn new(valves: &[Valve], start: &str) -> Result<Self> {
+        if !valves.iter().any(|v| v.name == start) {
+            return Err(Errors::InvalidState(format!("No valve named {}", start)).into());
+        }
+
+        let tunnels = Tunnels::new(valves);
+        let useful: Vec<&Valve> = valves.iter().filter(|v| v.flow != 0).collect();
 
-#[derive(Debug, Clone)]
-struct ValveOpen(usize, String);
+        if useful.len() > MAX_VALVES {
+            return Err(Errors::InvalidState(format!(
+                "{} valves with a flow, at most {} are supported",
+                useful.len(),
+                MAX_VALVES
+            ))
+            .into());
+        }
 
-impl Hash for ValveOpen {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.1.hash(state);
+        let open_times = useful
+            .iter()
+            .map(|v| v.name.as_str())
+            .chain([start])
+            .map(|from| {
+                useful
+                    .iter()
+                    .map(|to| tunnels.distance(from, &to.name).map(|d| d + 1))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Network {
+            names: useful.iter().map(|v| v.name.clone()).collect(),
+            flows: useful.iter().map(|v| v.flow).collect(),
+            open_times,
+        })
     }
-}
 
-impl PartialEq for ValveOpen {
-    fn eq(&self, other: &Self) -> bool {
-        self.1 == other.1
+    fn start(&self) -> usize {
+        self.flows.len()
     }
-}
 
-impl Eq for ValveOpen {}
-
-fn generate_all_paths(
-    options: HashSet<&str>,
-    max_time: usize,
-    mapping: &HashMap<String, Valve>,
-    connection_mapping: &HashMap<&str, Vec<String>>,
-    memorized_mapping: &mut HashMap<String, Vec<String>>,
-) -> Vec<PathWithScore> {
-    let mut all_paths: Vec<PathWithScore> = vec![];
-
-    let initial = ValveOpen(0, "AA".to_string());
-
-    let mut wip = VecDeque::new();
-    wip.push_back(StateWithTakeTime {
-        path: vec![],
-        visited: HashSet::new(),
-        time_so_far: 0,
-    });
-
-    while !wip.is_empty() {
-        let num = wip.len();
-        for _ in 0..num {
-            let state = wip.pop_front().unwrap();
-
-            if !state.path.is_empty() {
-                let path = HashSet::from_iter(state.path.iter().map(|m| m.clone()));
-                let score = scoree(path.iter(), mapping, max_time);
-                all_paths.push(PathWithScore(path, score));
-            }
+    /// Most pressure released in `time` minutes by opening exactly the valves
+    /// of each mask, 0 for sets that can't all be opened in time.
+    ///
+    /// Walks every (position, time left, opened) state, skipping a state when
+    /// it was already reached with at least as much pressure.
+    fn best_per_mask(&self, time: usize) -> Vec<usize> {
+        let mut best = vec![0; 1 << self.flows.len()];
+        let mut seen: HashMap<(usize, usize, usize), usize> = HashMap::new();
 
-            if state.time_so_far >= max_time {
-                continue;
-            }
+        let mut stack = vec![(self.start(), time, 0_usize, 0)];
 
-            let cur = &state.path.last().unwrap_or(&initial).1;
-            let difference = options.difference(&state.visited);
+        while let Some((position, time_left, opened, pressure)) = stack.pop() {
+            best[opened] = best[opened].max(pressure);
 
-            for option in difference.into_iter() {
-                let path = find_shortest_path(&cur, option, connection_mapping, memorized_mapping);
-                let mut new_state = state.clone();
-                new_state.time_so_far += path.len();
-                new_state
-                    .path
-                    .push(ValveOpen(new_state.time_so_far, option.to_string()));
-                new_state.visited.insert(option);
+            for (next, flow) in self.flows.iter().enumerate() {
+                if opened & (1 << next) != 0 {
+                    continue;
+                }
+
+                let Some(open_time) = self.open_times[position][next] else {
+                    continue;
+                };
 
-                wip.push_back(new_state);
+                if open_time >= time_left {
+                    continue;
+                }
+
+                let time_left = time_left - open_time;
+                let opened = opened | (1 << next);
+                let pressure = pressure + flow * time_left;
+
+                let state = (next, time_left, opened);
+                if seen.get(&state).is_some_and(|p| *p >= pressure) {
+                    continue;
+                }
+
+                seen.insert(state, pressure);
+                stack.push((next, time_left, opened, pressure));
             }
         }
+
+        best
     }
 
-    all_paths
-}
+    /// An order to open exactly the valves of `mask` in, releasing the most
+    /// pressure possible within `time`.
+    fn schedule(&self, time: usize, mask: usize) -> Vec<ValveOpen> {
+        let mut best = (0, vec![]);
+        let mut seen: HashMap<(usize, usize, usize), usize> = HashMap::new();
+        let mut stack = vec![(self.start(), time, 0_usize, 0, vec![])];
 
-fn find_shortest_path<'a>(
-    start: &'a str,
-    end: &'a str,
-    mapping: &'a HashMap<&'a str, Vec<String>>,
-    memorized_mapping: &'a mut HashMap<String, Vec<String>>,
-) -> Vec<String> {
-    let key = format!("{}-{}", start, end);
-    if memorized_mapping.contains_key(&key) {
-        return memorized_mapping.get(&key).unwrap().to_vec();
-    }
+        while let Some((position, time_left, opened, pressure, route)) = stack.pop() {
+            if opened == mask && pressure >= best.0 {
+                best = (pressure, route.clone());
+            }
 
-    let mut paths = VecDeque::new();
+            for next in (0..self.flows.len()).filter(|n| (mask & !opened) & (1 << n) != 0) {
+                let Some(open_time) = self.open_times[position][next] else {
+                    continue;
+                };
 
-    paths.push_back(State {
-        path: vec![start.to_string()],
-        visited: HashSet::new(),
-        time_so_far: 0,
-    });
+                if open_time >= time_left {
+                    continue;
+                }
 
-    while !paths.is_empty() {
-        let num_to_check = paths.len();
+                let time_left = time_left - open_time;
+                let pressure = pressure + self.flows[next] * time_left;
 
-        for _ in 0..num_to_check {
-            let mut state = paths.pop_front().unwrap();
-            let cur = state.path.last().unwrap();
+                let state = (next, time_left, opened | (1 << next));
+                if seen.get(&state).is_some_and(|p| *p >= pressure) {
+                    continue;
+                }
+                seen.insert(state, pressure);
 
-            if state.visited.contains(&cur.to_string()) {
-                continue;
-            }
+                let mut route = route.clone();
+                route.push(ValveOpen {
+                    minute: time - time_left,
+                    valve: self.names[next].clone(),
+                });
 
-            if *cur == end {
-                memorized_mapping.insert(key, state.path.clone());
-                return state.path.clone();
+                stack.push((next, time_left, state.2, pressure, route));
             }
+        }
 
-            state.visited.insert(cur.to_string());
+        best.1
+    }
+}
 
-            let other_paths = mapping.get(cur.as_str()).unwrap();
+/// For every mask, the best score among all of its subsets, with the subset
+/// that scores it.
+fn best_within_subsets(best: &[usize]) -> Vec<(usize, usize)> {
+    let mut within: Vec<(usize, usize)> = best.iter().copied().zip(0..).collect();
+    let bits = best.len().trailing_zeros();
 
-            for other_path in other_paths {
-                if !state.visited.contains(other_path) {
-                    let mut state_clone = state.clone();
-                    state_clone.path.push((&other_path).to_string());
-                    paths.push_back(state_clone);
-                }
+    for bit in 0..bits {
+        for mask in 0..within.len() {
+            if mask & (1 << bit) != 0 && within[mask ^ (1 << bit)].0 > within[mask].0 {
+                within[mask] = within[mask ^ (1 << bit)];
             }
         }
     }
 
-    vec![]
+    within
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, fs};
+    use std::fs;
 
-    use super::{find_shortest_path, scoree, Valve};
+    use aoc_core::Solution;
 
-    #[test]
-    fn test_calculate_complete_timings() {
+    use super::{best_within_subsets, plan, Day16, Network, Tunnels, Valve, ValveOpen};
+
+    fn example() -> Vec<Valve> {
         let input = fs::read_to_string("./inputs/day16_example.txt").unwrap();
-        let valves: Vec<Valve> = input.split('\n').map(|l| Valve::from(l)).collect();
-        let mut mapping: HashMap<&str, Valve> = HashMap::new();
-        let mut connected_mapping: HashMap<&str, Vec<String>> = HashMap::new();
+        Day16::parse(&input).unwrap()
+    }
 
-        valves.iter().for_each(|v| {
-            mapping.insert(&v.name, v.clone());
-            connected_mapping.insert(&v.name, v.connected.clone());
-        });
+    #[test]
+    fn test_example() {
+        let valves = example();
 
-        let timings = vec![
-            (2, "DD"),
-            (6, "JJ"),
-            (10, "BB"),
-            (17, "HH"),
-            (21, "EE"),
-            (24, "CC"),
-        ];
-        // let score = scoree(&timings, &mapping, 30);
+        assert_eq!(Day16::part1(&valves).unwrap(), 1651);
+        assert_eq!(Day16::part2(&valves).unwrap(), 1707);
+    }
 
-        // assert_eq!(score, 1649);
+    #[test]
+    fn test_best_per_mask() {
+        let valves = example();
+        let network = Network::new(&valves, "AA").unwrap();
+
+        let best = network.best_per_mask(30);
+        assert_eq!(best.len(), 1 << 6);
+        assert_eq!(best[0], 0);
+
+        // Walking straight to DD and opening it takes 2 minutes
+        let dd = valves
+            .iter()
+            .filter(|v| v.flow != 0)
+            .position(|v| v.name == "DD")
+            .unwrap();
+        assert_eq!(best[1 << dd], 20 * 28);
+
+        assert!(Network::new(&valves, "ZZ").is_err());
+    }
 
-        let timings = vec![
-            (2, "DD"),
-            (5, "BB"),
-            (9, "JJ"),
-            (17, "HH"),
-            (21, "EE"),
-            (24, "CC"),
-        ];
-        // let score = scoree(&timings, &mapping, 30);
+    #[test]
+    fn test_parse_valve() {
+        let valve: Valve = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB"
+            .parse()
+            .unwrap();
+        assert_eq!(valve.name, "AA");
+        assert_eq!(valve.flow, 0);
+        assert_eq!(valve.connected, vec!["DD", "II", "BB"]);
+
+        let valve: Valve = "Valve HH has flow rate=22; tunnel leads to valve GG"
+            .parse()
+            .unwrap();
+        assert_eq!((valve.flow, valve.connected), (22, vec!["GG".to_string()]));
+
+        let err = "Valve AA has flow rate=x; tunnels lead to valves BB"
+            .parse::<Valve>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 1, column 24: expected a number`"
+        );
+
+        assert!("Valve AA has flow rate=1; tunnels lead to valves BB,"
+            .parse::<Valve>()
+            .is_err());
+        assert!("Valve AA has flow rate=1; tunnels go to valves BB"
+            .parse::<Valve>()
+            .is_err());
+    }
 
-        // assert_eq!(score, 1651);
+    #[test]
+    fn test_parse_errors() {
+        let input = "Valve AA has flow rate=0; tunnel leads to valve BB\n\
+                     Valve BB has flow rate=3; tunnels lead to valves AA, CC";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2: valve BB leads to unknown valve CC`"
+        );
+
+        let input = "Valve AA has flow rate=0; tunnel leads to valve AA\nValve AA";
+        let err = Day16::parse(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2, column 9: expected ` has flow rate=``"
+        );
+    }
+
+    #[test]
+    fn test_best_within_subsets() {
+        let best = best_within_subsets(&[0, 5, 3, 4]);
+
+        assert_eq!(best, vec![(0, 0), (5, 1), (3, 2), (5, 1)]);
+    }
+
+    fn opens(schedule: &[ValveOpen]) -> Vec<(usize, &str)> {
+        schedule
+            .iter()
+            .map(|open| (open.minute, open.valve.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan() {
+        let valves = example();
+
+        let alone = plan(&valves, "AA", 30, 1).unwrap();
+        assert_eq!(alone.pressure, 1651);
+        assert_eq!(
+            opens(&alone.schedules[0]),
+            vec![
+                (2, "DD"),
+                (5, "BB"),
+                (9, "JJ"),
+                (17, "HH"),
+                (21, "EE"),
+                (24, "CC")
+            ]
+        );
+
+        let pair = plan(&valves, "AA", 26, 2).unwrap();
+        assert_eq!(pair.pressure, 1707);
+        assert_eq!(
+            opens(&pair.schedules[0]),
+            vec![(2, "DD"), (7, "HH"), (11, "EE")]
+        );
+        assert_eq!(
+            opens(&pair.schedules[1]),
+            vec![(3, "JJ"), (7, "BB"), (9, "CC")]
+        );
+
+        assert!(plan(&valves, "AA", 26, 0).is_err());
+    }
+
+    #[test]
+    fn test_plan_variants() {
+        let valves = example();
+
+        // A third actor only has fewer valves left to walk to
+        let trio = plan(&valves, "AA", 26, 3).unwrap();
+        assert!(trio.pressure >= 1707);
+        assert_eq!(trio.schedules.len(), 3);
+
+        let opened: Vec<&str> = trio
+            .schedules
+            .iter()
+            .flatten()
+            .map(|open| open.valve.as_str())
+            .collect();
+        let mut unique = opened.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(opened.len(), unique.len());
+
+        // Scores of the schedules add up to the plan's pressure
+        let released: usize = trio
+            .schedules
+            .iter()
+            .flatten()
+            .map(|open| {
+                let flow = valves.iter().find(|v| v.name == open.valve).unwrap().flow;
+                flow * (26 - open.minute)
+            })
+            .sum();
+        assert_eq!(released, trio.pressure);
+
+        let short = plan(&valves, "AA", 20, 1).unwrap();
+        assert!(short.pressure < 1651);
+        assert!(short.schedules[0].iter().all(|open| open.minute < 20));
     }
 
     #[test]
     fn test_shortest_path() {
-        let other_mapping: HashMap<&str, Vec<String>> = HashMap::from([
-            (
-                "AA",
-                vec!["DD".to_string(), "II".to_string(), "BB".to_string()],
-            ),
-            ("BB", vec!["CC".to_string(), "AA".to_string()]),
-            ("CC", vec!["DD".to_string(), "BB".to_string()]),
-            (
-                "DD",
-                vec!["CC".to_string(), "AA".to_string(), "EE".to_string()],
-            ),
-            ("EE", vec!["FF".to_string(), "DD".to_string()]),
-            ("FF", vec!["EE".to_string(), "GG".to_string()]),
-            ("GG", vec!["FF".to_string(), "HH".to_string()]),
-            ("HH", vec!["GG".to_string()]),
-            ("II", vec!["AA".to_string(), "JJ".to_string()]),
-            ("JJ", vec!["II".to_string()]),
-        ]);
-
-        let mut memory = HashMap::new();
-        let path = find_shortest_path(&"AA", &"JJ", &other_mapping, &mut memory);
-
-        assert_eq!(path.len(), 3);
+        let valves = [
+            Valve::new("AA", 0, vec!["DD", "II", "BB"]),
+            Valve::new("BB", 13, vec!["CC", "AA"]),
+            Valve::new("CC", 2, vec!["DD", "BB"]),
+            Valve::new("DD", 20, vec!["CC", "AA", "EE"]),
+            Valve::new("EE", 3, vec!["FF", "DD"]),
+            Valve::new("FF", 0, vec!["EE", "GG"]),
+            Valve::new("GG", 0, vec!["FF", "HH"]),
+            Valve::new("HH", 22, vec!["GG"]),
+            Valve::new("II", 0, vec!["AA", "JJ"]),
+            Valve::new("JJ", 21, vec!["II"]),
+        ];
+
+        let tunnels = Tunnels::new(&valves);
+
+        assert_eq!(tunnels.distance("AA", "JJ"), Some(2));
+        assert_eq!(tunnels.distance("HH", "JJ"), Some(7));
+        assert_eq!(tunnels.distance("AA", "ZZ"), None);
     }
 }
@@ -1,65 +1,203 @@
+#[cfg(test)]
+use std::collections::HashSet;
 use std::{
     cmp::max,
-    collections::{HashMap, HashSet},
-    fs,
+    collections::HashMap,
+    fmt::{self, Display},
 };
 
 use anyhow::Result;
 
-use crate::{Coord, Errors};
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Answer, Errors, Solution,
+};
+
+use crate::Coord;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<Direction>;
+    type Part1 = isize;
+    type Part2 = Tower;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let jets = parse::all(input.trim_end(), |input| {
+            let mut jets = vec![];
+            while !input.is_empty() {
+                jets.push(Direction::parse(input)?);
+            }
+
+            Ok(jets)
+        });
+
+        Ok(jets.map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
+
+fn solve_part_1(directions: &[Direction]) -> Result<isize> {
+    Ok(simulate(directions, 2022)?.height)
+}
+
+fn solve_part_2(directions: &[Direction]) -> Result<Tower> {
+    simulate(directions, 1000000000000)
+}
 
-pub fn exec() -> Result<()> {
-    let input_example = fs::read_to_string("./inputs/day17_example.txt")?;
-    let input = fs::read_to_string("./inputs/day17.txt")?;
-    solve_part_1(&input_example)?;
-    solve_part_1(&input)?;
-    solve_part_2(&input_example)?;
-    Ok(())
+/// What the simulation looks like just before a rock falls: which shape
+/// and jet come next, and the top rows of the tower from
+/// [`Terrian::find_and_normalize`]. Everything that decides where later
+/// rocks land is in here, so equal fingerprints play out the same.
+#[derive(PartialEq, Eq, Hash)]
+struct Fingerprint {
+    shape: usize,
+    jet: usize,
+    rows: Vec<u8>,
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let highest_point = solve(input, 2022);
+/// A repeating stretch of rocks: after the first `offset` rocks, every
+/// `length` rocks add `height` to the tower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cycle {
+    offset: usize,
+    length: usize,
+    height: isize,
+}
 
-    println!("Day 17-1: {}", highest_point);
-    Ok(())
+/// Height of the tower once all rocks fell, and the cycle that was skipped
+/// to get there.
+#[derive(Debug)]
+pub struct Tower {
+    height: isize,
+    cycle: Option<Cycle>,
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let highest_point = solve(input, 1000000000000);
+/// Shows only the height, the alternate form `{:#}` adds the cycle.
+impl Display for Tower {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.height)?;
+
+        if let (true, Some(cycle)) = (f.alternate(), self.cycle) {
+            write!(f, " ({})", cycle)?;
+        }
 
-    println!("Day 17-2: {}", highest_point);
-    Ok(())
+        Ok(())
+    }
 }
 
-fn solve(input: &str, total_shapes: usize) -> isize {
-    let directions: Vec<Direction> = input
-        .as_bytes()
-        .iter()
-        .filter_map(|s| Direction::try_from(*s).ok())
-        .collect();
-
-    let mut terrian = Terrian::new_with_coords(vec![
-        Coord::new(0, 0),
-        Coord::new(1, 0),
-        Coord::new(2, 0),
-        Coord::new(3, 0),
-        Coord::new(4, 0),
-        Coord::new(5, 0),
-        Coord::new(6, 0),
-    ]);
-
-    let binding = get_shapes();
-    let mut shapes = binding.iter().cycle();
-    let mut directions = directions.iter().cycle();
-
-    run(&mut terrian, &mut shapes, &mut directions, 0, total_shapes)
+impl Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cycle of {} rocks adding {} rows, after {} rocks",
+            self.length, self.height, self.offset
+        )
+    }
 }
 
-struct Terrian(HashMap<isize, HashSet<Coord>>);
+/// The height, with the cycle as a note when one was skipped.
+impl From<Tower> for Answer {
+    fn from(tower: Tower) -> Self {
+        let height = Answer::from(tower.height);
+
+        match tower.cycle {
+            Some(cycle) => height.with_note(cycle.to_string()),
+            None => height,
+        }
+    }
+}
+
+fn simulate(directions: &[Direction], total_shapes: usize) -> Result<Tower> {
+    if directions.is_empty() {
+        return Err(Errors::InvalidState("No jets to push the rocks".into()).into());
+    }
+
+    let mut terrian = Terrian::new_with_coords((0..BOUNDS).map(|x| Coord::new(x, 0)));
+
+    let shapes = get_shapes();
+    let mut jets = Jets {
+        pattern: directions,
+        next: 0,
+    };
+
+    let mut seen: HashMap<Fingerprint, (usize, isize)> = HashMap::new();
+    let mut highest_point = 0;
+    let mut skipped_height = 0;
+    let mut cycle = None;
+    let mut fallen = 0;
+
+    while fallen < total_shapes {
+        let shape = &shapes[fallen % shapes.len()];
+        highest_point = max(
+            highest_point,
+            try_move(&mut terrian, shape, &mut jets, highest_point),
+        );
+        fallen += 1;
+
+        if cycle.is_some() {
+            continue;
+        }
+
+        let fingerprint = Fingerprint {
+            shape: fallen % shapes.len(),
+            jet: jets.next,
+            rows: terrian.find_and_normalize(highest_point),
+        };
+
+        if let Some((offset, height)) = seen.insert(fingerprint, (fallen, highest_point)) {
+            let found = Cycle {
+                offset,
+                length: fallen - offset,
+                height: highest_point - height,
+            };
+
+            let periods = (total_shapes - fallen) / found.length;
+            fallen += periods * found.length;
+            skipped_height = periods as isize * found.height;
+            cycle = Some(found);
+        }
+    }
+
+    Ok(Tower {
+        height: highest_point + skipped_height,
+        cycle,
+    })
+}
+
+/// The jet pattern repeated forever, remembering which jet comes next.
+struct Jets<'a> {
+    pattern: &'a [Direction],
+    next: usize,
+}
+
+impl<'a> Iterator for Jets<'a> {
+    type Item = &'a Direction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let direction = self.pattern.get(self.next)?;
+        self.next = (self.next + 1) % self.pattern.len();
+
+        Some(direction)
+    }
+}
+
+/// The chamber, one byte per row from the floor up, with bit `x` set when
+/// column `x` is taken.
+struct Terrian {
+    rows: Vec<u8>,
+}
 
 impl Terrian {
     fn new() -> Self {
-        Terrian(HashMap::new())
+        Terrian { rows: Vec::new() }
     }
 
     fn new_with_coords<I>(coords: I) -> Self
@@ -68,167 +206,166 @@ impl Terrian {
     {
         let mut terrian = Self::new();
 
-        terrian.lock_in(coords);
+        for coord in coords {
+            *terrian.row_mut(coord.y) |= 1 << coord.x;
+        }
 
         terrian
     }
 
-    fn is_valid_move(&self, moves: &HashSet<Coord>) -> bool {
-        if moves.iter().any(|c| c.x < 0 || c.x >= BOUNDS) {
+    /// Anything below the floor counts as taken.
+    fn row(&self, y: isize) -> u8 {
+        match usize::try_from(y) {
+            Ok(y) => self.rows.get(y).copied().unwrap_or(0),
+            Err(_) => FULL_ROW,
+        }
+    }
+
+    fn row_mut(&mut self, y: isize) -> &mut u8 {
+        let y = usize::try_from(y).expect("rows below the floor are never written");
+        if y >= self.rows.len() {
+            self.rows.resize(y + 1, 0);
+        }
+
+        &mut self.rows[y]
+    }
+
+    /// Whether `shape` with its bottom left corner at `at` is inside the
+    /// walls and overlaps nothing.
+    fn is_valid_move(&self, shape: &Shape, at: Coord) -> bool {
+        if at.x < 0 || at.x + shape.width > BOUNDS {
             return false;
         }
 
-        !moves
+        shape
+            .rows
             .iter()
-            .any(|c| self.0.get(&c.y).map_or(false, |set| set.contains(&c)))
+            .zip(at.y..)
+            .all(|(row, y)| self.row(y) & (row << at.x) == 0)
     }
 
-    fn lock_in<I>(&mut self, moves: I)
-    where
-        I: IntoIterator<Item = Coord>,
-    {
-        moves.into_iter().for_each(|c| {
-            self.0
-                .entry(c.y)
-                .and_modify(|h| {
-                    h.insert(c);
-                })
-                .or_insert(HashSet::from_iter(vec![c.clone()]));
-        });
+    fn lock_in(&mut self, shape: &Shape, at: Coord) {
+        for (row, y) in shape.rows.iter().zip(at.y..) {
+            *self.row_mut(y) |= row << at.x;
+        }
     }
 
+    #[cfg(test)]
     fn difference(&self, coords: &HashSet<Coord>) -> Vec<Coord> {
         coords
             .iter()
-            .filter_map(|c| {
-                self.0
-                    .get(&c.y)
-                    .filter(|h| !h.contains(&c))
-                    .and_then(|_| Some(c))
-            })
-            .map(|c| c.clone())
+            .filter(|c| self.row(c.y) != 0 && self.row(c.y) & (1 << c.x) == 0)
+            .copied()
             .collect()
     }
 
-    fn find_and_normalize(&self, row: isize, normalize_to: isize) -> HashSet<Coord> {
-        self.0
-            .get(&row)
-            .map(|h| h.iter().map(|c| c.normalize_y_to(normalize_to)).collect())
-            .unwrap_or(HashSet::new())
-    }
-}
-
-fn run<'a, 'b>(
-    terrian: &mut Terrian,
-    shapes: &mut impl Iterator<Item = &'b Shape>,
-    directions: &mut impl Iterator<Item = &'a Direction>,
-    highest_point: isize,
-    total_shapes: usize,
-) -> isize {
-    let mut highest_point = highest_point;
-    let mut taken = 0;
-
-    while let Some(shape) = shapes.next() {
-        highest_point = max(
-            highest_point,
-            try_move(terrian, &shape, directions, highest_point),
-        );
-
-        taken += 1;
-        if taken == total_shapes {
-            break;
+    /// The rows from `highest_point` down that a falling rock can still
+    /// reach, so towers of any height can be compared. Rocks only move down
+    /// and sideways, so this follows the air open to the top row by row,
+    /// ending with the row that closes it off. Nothing under it matters.
+    fn find_and_normalize(&self, highest_point: isize) -> Vec<u8> {
+        let mut rows = vec![];
+        let mut open = FULL_ROW;
+
+        for depth in 0.. {
+            let row = self.row(highest_point - depth);
+            rows.push(row);
+
+            open &= !row;
+            loop {
+                let spread = (open | open << 1 | open >> 1) & !row & FULL_ROW;
+                if spread == open {
+                    break;
+                }
+
+                open = spread;
+            }
+
+            if open == 0 {
+                break;
+            }
         }
-    }
 
-    highest_point
+        rows
+    }
 }
 
+const FULL_ROW: u8 = (1 << BOUNDS) - 1;
+
+/// A rock as row masks from its bottom up, bit `x` being `x` columns right
+/// of its left edge.
 #[derive(Debug, Clone)]
-struct Shape(HashSet<Coord>);
+struct Shape {
+    rows: &'static [u8],
+    width: isize,
+}
 
-fn get_shapes() -> Vec<Shape> {
-    vec![
+fn get_shapes() -> [Shape; 5] {
+    [
         // ---
-        Shape(HashSet::from_iter(vec![
-            Coord::new(-1, 0),
-            Coord::new(0, 0),
-            Coord::new(1, 0),
-            Coord::new(2, 0),
-        ])),
+        Shape {
+            rows: &[0b1111],
+            width: 4,
+        },
         // +
-        Shape(HashSet::from_iter(vec![
-            Coord::new(0, 0),
-            Coord::new(0, -1),
-            Coord::new(0, -2),
-            Coord::new(-1, -1),
-            Coord::new(1, -1),
-        ])),
+        Shape {
+            rows: &[0b010, 0b111, 0b010],
+            width: 3,
+        },
         // L
-        Shape(HashSet::from_iter(vec![
-            Coord::new(1, 0),
-            Coord::new(1, -1),
-            Coord::new(1, -2),
-            Coord::new(0, -2),
-            Coord::new(-1, -2),
-        ])),
+        Shape {
+            rows: &[0b111, 0b100, 0b100],
+            width: 3,
+        },
         // l
-        Shape(HashSet::from_iter(vec![
-            Coord::new(-1, 0),
-            Coord::new(-1, -1),
-            Coord::new(-1, -2),
-            Coord::new(-1, -3),
-        ])),
+        Shape {
+            rows: &[0b1, 0b1, 0b1, 0b1],
+            width: 1,
+        },
         // o
-        Shape(HashSet::from_iter(vec![
-            Coord::new(0, 0),
-            Coord::new(0, -1),
-            Coord::new(-1, 0),
-            Coord::new(-1, -1),
-        ])),
+        Shape {
+            rows: &[0b11, 0b11],
+            width: 2,
+        },
     ]
 }
 
 impl Shape {
-    fn transform_by(&self, coord: Coord) -> HashSet<Coord> {
-        self.0.iter().map(|c| c.adjust(&coord)).collect()
-    }
-
-    fn transform(&mut self, coord: Coord) {
-        self.0 = self.transform_by(coord);
-    }
-
-    fn highest_point(&self) -> isize {
-        self.0.iter().map(|c| c.y).max().unwrap_or(0)
+    /// Cells covered with the bottom left corner at `at`.
+    #[cfg(test)]
+    fn cells(&self, at: Coord) -> HashSet<Coord> {
+        self.rows
+            .iter()
+            .zip(at.y..)
+            .flat_map(|(row, y)| {
+                (0..self.width)
+                    .filter(move |x| row & (1 << x) != 0)
+                    .map(move |x| Coord::new(at.x + x, y))
+            })
+            .collect()
     }
 
-    fn lowest_point(&self) -> Coord {
-        Coord {
-            y: self.0.iter().map(|c| c.y).min().unwrap_or(0),
-            x: self.0.iter().map(|c| c.x).min().unwrap_or(0),
-        }
+    fn highest_point(&self, at: Coord) -> isize {
+        at.y + self.rows.len() as isize - 1
     }
 }
 
 #[derive(Clone, Copy)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
     Down,
 }
 
-impl TryFrom<u8> for Direction {
-    type Error = Errors;
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            b'<' => Ok(Self::Left),
-            b'>' => Ok(Self::Right),
-            _ => Err(Errors::ParseError("Invalid character for direction".into())),
+impl Direction {
+    /// A jet of gas, `<` or `>`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        match input.one_of(&["<", ">"])? {
+            "<" => Ok(Self::Left),
+            _ => Ok(Self::Right),
         }
     }
-}
 
-impl Direction {
     fn coord(&self) -> Coord {
         match self {
             Self::Left => Coord::new(-1, 0),
@@ -245,63 +382,63 @@ fn try_move<'a>(
     directions: &mut impl Iterator<Item = &'a Direction>,
     highest_point: isize,
 ) -> isize {
-    let mut shape = shape.clone();
-    let lowest_pt = shape.lowest_point();
-
     // Put it on the starting position
-    shape.transform(Coord::new(
-        2 + lowest_pt.x.abs(),
-        highest_point + 4 + lowest_pt.y.abs(),
-    ));
+    let mut at = Coord::new(2, highest_point + 4);
 
     loop {
         let direction = directions.next().unwrap();
-        let new_pos: HashSet<Coord> = shape.transform_by(direction.coord());
 
         // If the space is empty and isn't out of bounds
-        if terrian.is_valid_move(&new_pos) {
-            shape.transform(direction.coord());
+        if terrian.is_valid_move(shape, at + direction.coord()) {
+            at += direction.coord();
         }
 
-        // Gravity hits
-        let new_pos: HashSet<Coord> = shape.transform_by(Direction::Down.coord());
-
-        // If the space isn't empty it means it'll come to a stop
-        if !terrian.is_valid_move(&new_pos) {
+        // If the space below isn't empty it means it'll come to a stop
+        let below = at + Direction::Down.coord();
+        if !terrian.is_valid_move(shape, below) {
             break;
         }
 
-        shape.transform(Direction::Down.coord());
+        at = below;
     }
 
-    let highest_point = shape.highest_point();
+    terrian.lock_in(shape, at);
 
-    terrian.lock_in(shape.0);
-
-    highest_point
+    shape.highest_point(at)
 }
 
 #[cfg(test)]
 mod tests {
     use std::{cmp::max, collections::HashSet, fs};
 
+    use aoc_core::{Answer, Solution};
+
     use crate::{
         day17::{try_move, Terrian},
         Coord,
     };
 
-    use super::{get_shapes, Direction, Shape};
+    use super::{get_shapes, simulate, Cycle, Day17, Direction, Shape};
+
+    fn example() -> Vec<Direction> {
+        let input = fs::read_to_string("./inputs/day17_example.txt").unwrap();
+        Day17::parse(&input).unwrap()
+    }
 
     #[test]
     fn test_positioning() {
-        let mut shapes: Vec<Shape> = get_shapes();
+        let shapes: [Shape; 5] = get_shapes();
 
-        shapes
-            .iter_mut()
-            .for_each(|s| s.transform(Coord::new(3, 4)));
+        // Each one placed so that its top row is row 4
+        let corners = [(2, 4), (2, 2), (2, 2), (2, 1), (2, 3)];
+        let shapes: Vec<HashSet<Coord>> = shapes
+            .iter()
+            .zip(corners)
+            .map(|(s, (x, y))| s.cells(Coord::new(x, y)))
+            .collect();
 
         assert_eq!(
-            shapes[0].0.clone().into_iter().collect::<HashSet<Coord>>(),
+            shapes[0],
             HashSet::from_iter(vec![
                 Coord::new(2, 4),
                 Coord::new(3, 4),
@@ -311,7 +448,7 @@ mod tests {
         );
 
         assert_eq!(
-            shapes[1].0.clone().into_iter().collect::<HashSet<Coord>>(),
+            shapes[1],
             HashSet::from_iter(vec![
                 Coord::new(3, 3),
                 Coord::new(3, 4),
@@ -322,7 +459,7 @@ mod tests {
         );
 
         assert_eq!(
-            shapes[2].0.clone().into_iter().collect::<HashSet<Coord>>(),
+            shapes[2],
             HashSet::from_iter(vec![
                 Coord::new(4, 4),
                 Coord::new(4, 3),
@@ -333,7 +470,7 @@ mod tests {
         );
 
         assert_eq!(
-            shapes[3].0.clone().into_iter().collect::<HashSet<Coord>>(),
+            shapes[3],
             HashSet::from_iter(vec![
                 Coord::new(2, 4),
                 Coord::new(2, 3),
@@ -343,7 +480,7 @@ mod tests {
         );
 
         assert_eq!(
-            shapes[4].0.clone().into_iter().collect::<HashSet<Coord>>(),
+            shapes[4],
             HashSet::from_iter(vec![
                 Coord::new(2, 4),
                 Coord::new(2, 3),
@@ -356,11 +493,7 @@ mod tests {
     #[test]
     fn test_objects_falling() {
         let input_example = fs::read_to_string("./inputs/day17_example.txt").unwrap();
-        let directions: Vec<Direction> = input_example
-            .as_bytes()
-            .iter()
-            .filter_map(|s| Direction::try_from(*s).ok())
-            .collect();
+        let directions = Day17::parse(&input_example).unwrap();
 
         let expected = vec![
             Coord::new(0, 0),
@@ -472,10 +605,7 @@ mod tests {
 
         // o
         let shape = shapes.next().unwrap();
-        highest_point = max(
-            highest_point,
-            try_move(&mut terrian, shape, &mut directions, highest_point),
-        );
+        try_move(&mut terrian, shape, &mut directions, highest_point);
 
         // o
         expected.extend(vec![
@@ -489,4 +619,54 @@ mod tests {
         println!("Difference o: {:?}", difference);
         assert_eq!(difference.len(), 0);
     }
+
+    #[test]
+    fn test_top_rows() {
+        let floor = (0..7).map(|x| Coord::new(x, 0));
+        let rocks = [Coord::new(1, 1), Coord::new(1, 2), Coord::new(4, 1)];
+        let terrian = Terrian::new_with_coords(floor.clone().chain(rocks));
+
+        assert_eq!(terrian.find_and_normalize(2), [0b10, 0b10010, 0b1111111]);
+        assert_eq!(terrian.find_and_normalize(3), [0, 0b10, 0b10010, 0b1111111]);
+
+        // A lid with a gap leaves the air under it reachable from the side,
+        // without the gap nothing under it counts
+        let lid = (1..7).map(|x| Coord::new(x, 2));
+        let terrian = Terrian::new_with_coords(floor.clone().chain(lid.clone()));
+        assert_eq!(terrian.find_and_normalize(2), [0b1111110, 0, 0b1111111]);
+
+        let lid = lid.chain([Coord::new(0, 2)]);
+        let terrian = Terrian::new_with_coords(floor.chain(lid));
+        assert_eq!(terrian.find_and_normalize(2), [0b1111111]);
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let directions = example();
+
+        let tower = simulate(&directions, 1000000000000).unwrap();
+        assert_eq!(tower.height, 1514285714288);
+        assert_eq!(
+            tower.cycle,
+            Some(Cycle {
+                offset: 28,
+                length: 35,
+                height: 53
+            })
+        );
+
+        assert_eq!(
+            format!("{:#}", tower),
+            "1514285714288 (cycle of 35 rocks adding 53 rows, after 28 rocks)"
+        );
+        let answer = Answer::from(Day17::part2(&directions).unwrap());
+        assert_eq!(answer.to_string(), "1514285714288");
+        assert_eq!(
+            answer.note(),
+            Some("cycle of 35 rocks adding 53 rows, after 28 rocks")
+        );
+
+        // Skipping periods gives the same height as dropping every rock
+        assert_eq!(simulate(&directions, 2022).unwrap().height, 3068);
+    }
 }
@@ -1,130 +1,193 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
 
-use crate::Errors;
+use aoc_core::{
+    graph,
+    parse::{self, Input, ParseError},
+    Answer, DisjointSet, Errors, Solution,
+};
 
-pub fn exec() -> Result<()> {
-    let input_example = fs::read_to_string("./inputs/day18_example.txt")?;
-    let input = fs::read_to_string("./inputs/day18.txt")?;
+pub struct Day18;
 
-    solve_part_1(&input_example)?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
+impl Solution for Day18 {
+    type Input = Vec<Cube>;
+    type Part1 = usize;
+    type Part2 = Exterior;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input.trim(), Cube::parse).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let cubes: Vec<Cube> = input.trim().split('\n').map(|s| Cube::from(s)).collect();
-    let mut plane = Plane::new(cubes);
+fn solve_part_1(cubes: &[Cube]) -> Result<usize> {
+    let mut plane = Plane::new(cubes.iter().copied());
 
-    plane.link_connected_cubes()?;
+    plane.link_connected_cubes();
 
-    println!("Day 18-1: {}", plane.calculate_surface_area());
-    Ok(())
+    Ok(plane.calculate_surface_area())
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    println!("Day 18-2: {}", "");
-    Ok(())
-}
+fn solve_part_2(cubes: &[Cube]) -> Result<Exterior> {
+    let droplet = Droplet::new(cubes);
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
-struct Cube(isize, isize, isize);
+    let mut pockets: Vec<Vec<Cube>> = droplet.air_pockets().into_values().collect();
+    pockets.iter_mut().for_each(|pocket| pocket.sort());
+    pockets.sort();
 
-struct Plane {
-    shapes: HashMap<Cube, Cube>,
-    rank: HashMap<Cube, usize>,
+    Ok(Exterior {
+        area: droplet.exterior_surface_area(),
+        pockets,
+    })
 }
 
-impl Plane {
-    fn new(cubes: impl IntoIterator<Item = Cube>) -> Self {
-        let mut shapes = HashMap::new();
-        let mut rank = HashMap::new();
-        cubes.into_iter().for_each(|cube| {
-            shapes.insert(cube, cube);
-            rank.insert(cube, 1);
-        });
+/// The surface area facing outside, and the pockets of air trapped inside
+/// that it leaves out, in order.
+#[derive(Debug)]
+pub struct Exterior {
+    pub area: usize,
+    pub pockets: Vec<Vec<Cube>>,
+}
 
-        Plane { shapes, rank }
+/// The area, with the air pockets as a note.
+impl From<Exterior> for Answer {
+    fn from(exterior: Exterior) -> Self {
+        let air: usize = exterior.pockets.iter().map(Vec::len).sum();
+        let note = format!(
+            "air pockets trapped inside: {}, cubes of air in them: {}",
+            exterior.pockets.len(),
+            air
+        );
+
+        Answer::from(exterior.area).with_note(note)
     }
+}
 
-    fn find(&mut self, cube: Cube) -> Result<Cube> {
-        let p = self
-            .shapes
-            .get(&cube)
-            .ok_or(Errors::InvalidState("Root doesn't exist".into()))?
-            .clone();
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Cube(isize, isize, isize);
 
-        if cube == p {
-            return Ok(cube);
+impl Cube {
+    /// A position like `2,2,2`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        match input.separated(",", |i| i.signed())?[..] {
+            [x, y, z] => Ok(Cube(x, y, z)),
+            _ => Err(input.error("expected three coordinates")),
         }
+    }
+}
 
-        let p_root = self.find(p)?;
-        self.shapes.insert(cube, p_root);
+struct Plane {
+    shapes: DisjointSet<Cube>,
+}
 
-        Ok(p_root)
+impl Plane {
+    fn new(cubes: impl IntoIterator<Item = Cube>) -> Self {
+        Plane {
+            shapes: cubes.into_iter().collect(),
+        }
     }
 
-    fn get_rank(&self, cube: Cube) -> Result<usize, Errors> {
-        self.rank
-            .get(&cube)
-            .ok_or(Errors::InvalidState("Rank doesn't exist".into()))
-            .map(|r| *r)
+    fn union(&mut self, left: Cube, right: Cube) {
+        self.shapes.union(left, right);
     }
 
-    fn union(&mut self, left: Cube, right: Cube) -> Result<()> {
-        let mut left_root = self.find(left)?;
-        let mut right_root = self.find(right)?;
-
-        if left_root != right_root {
-            let left_rank = self.get_rank(left_root)?;
-            let right_rank = self.get_rank(right_root)?;
+    fn get_shapes(&self) -> HashMap<Cube, Vec<Cube>> {
+        self.shapes
+            .components()
+            .into_iter()
+            .map(|(root, cubes)| (*root, cubes.into_iter().copied().collect()))
+            .collect()
+    }
 
-            if left_rank < right_rank {
-                let tmp = right_root;
-                right_root = left_root;
-                left_root = tmp;
-            }
+    fn link_connected_cubes(&mut self) {
+        let mut cubes: Vec<Cube> = self.shapes.iter().copied().collect();
+        cubes.sort();
 
-            self.shapes.insert(right_root, left_root);
-            if left_rank == right_rank {
-                self.rank.entry(left_root).and_modify(|rank| *rank += 1);
+        for cube in cubes {
+            for neighbor in cube.neighbors() {
+                if self.shapes.contains(&neighbor) {
+                    self.union(cube, neighbor);
+                }
             }
         }
+    }
+}
+
+/// The lava cubes inside a box one cube larger than them on every side, with
+/// the air reachable from outside flood-filled from a corner of the box.
+pub struct Droplet {
+    lava: HashSet<Cube>,
+    min: Cube,
+    max: Cube,
+    outside: HashSet<Cube>,
+}
+
+impl Droplet {
+    pub fn new(cubes: &[Cube]) -> Self {
+        let lava: HashSet<Cube> = cubes.iter().copied().collect();
+
+        let axis = |f: fn(&Cube) -> isize| {
+            let min = cubes.iter().map(f).min().unwrap_or_default();
+            let max = cubes.iter().map(f).max().unwrap_or_default();
+            (min - 1, max + 1)
+        };
+        let (x, y, z) = (axis(|c| c.0), axis(|c| c.1), axis(|c| c.2));
+        let min = Cube(x.0, y.0, z.0);
+        let max = Cube(x.1, y.1, z.1);
+
+        let mut droplet = Droplet {
+            lava,
+            min,
+            max,
+            outside: HashSet::new(),
+        };
+
+        let outside = graph::bfs([min], |cube| {
+            cube.neighbors()
+                .into_iter()
+                .filter(|n| droplet.within(n) && !droplet.lava.contains(n))
+                .collect::<Vec<_>>()
+        });
+        droplet.outside = outside.iter().map(|(cube, _)| *cube).collect();
 
-        Ok(())
+        droplet
     }
 
-    fn get_shapes(&mut self) -> Result<HashMap<Cube, Vec<Cube>>> {
-        let mut results: HashMap<Cube, Vec<Cube>> = HashMap::new();
-        let cubes: Vec<Cube> = self.shapes.keys().copied().collect();
-        for cube in cubes {
-            let root = self.find(cube)?;
-            results
-                .entry(root)
-                .and_modify(|v| v.push(cube))
-                .or_insert_with(|| vec![cube]);
-        }
+    fn within(&self, cube: &Cube) -> bool {
+        (self.min.0..=self.max.0).contains(&cube.0)
+            && (self.min.1..=self.max.1).contains(&cube.1)
+            && (self.min.2..=self.max.2).contains(&cube.2)
+    }
 
-        Ok(results)
+    /// Lava faces touching the air outside. Every such face is inside the
+    /// box, since the box leaves a layer of air around the droplet.
+    pub fn exterior_surface_area(&self) -> usize {
+        self.lava
+            .iter()
+            .flat_map(|cube| cube.neighbors())
+            .filter(|n| self.outside.contains(n))
+            .count()
     }
 
-    fn link_connected_cubes(&mut self) -> Result<()> {
-        let cubes: HashSet<Cube> = self.shapes.keys().copied().collect();
-        let mut sorted_cubes: Vec<Cube> = self.shapes.keys().copied().collect::<Vec<Cube>>();
-        sorted_cubes.sort();
-        for cube in &sorted_cubes {
-            for neighbor in cube.neighbors().iter().filter(|n| cubes.contains(n)) {
-                self.union(*cube, *neighbor)?;
-            }
-        }
+    /// Air the flood fill could not reach, grouped into separate pockets.
+    pub fn air_pockets(&self) -> HashMap<Cube, Vec<Cube>> {
+        let trapped = (self.min.0..=self.max.0)
+            .flat_map(|x| (self.min.1..=self.max.1).map(move |y| (x, y)))
+            .flat_map(|(x, y)| (self.min.2..=self.max.2).map(move |z| Cube(x, y, z)))
+            .filter(|cube| !self.lava.contains(cube) && !self.outside.contains(cube));
+
+        let mut pockets = Plane::new(trapped);
+        pockets.link_connected_cubes();
 
-        Ok(())
+        pockets.get_shapes()
     }
 }
 
@@ -165,87 +228,130 @@ impl SurfaceArea for Vec<Cube> {
 
 impl SurfaceAreaWithMutation for Plane {
     fn calculate_surface_area(&mut self) -> usize {
-        if self.link_connected_cubes().is_err() {
-            return 0;
-        }
-
-        let shapes = self.get_shapes().unwrap_or_default();
+        self.link_connected_cubes();
 
-        shapes
-            .iter()
-            .map(|(_k, v)| v.calculate_surface_area())
+        self.get_shapes()
+            .values()
+            .map(|v| v.calculate_surface_area())
             .sum()
     }
 }
 
-impl From<&str> for Cube {
-    fn from(value: &str) -> Self {
-        let split: Vec<isize> = value
-            .split(',')
-            .map(|c| c.parse::<isize>().unwrap_or_default())
-            .collect();
+impl TryFrom<&str> for Cube {
+    type Error = Errors;
 
-        Cube(split[0], split[1], split[2])
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(parse::all(value, Cube::parse)?)
     }
 }
 
 // ########### Tests
 #[cfg(test)]
 mod tests {
-    use crate::day18::{Cube, SurfaceArea};
-    use anyhow::Result;
+    use std::fs;
+
+    use aoc_core::{Answer, Solution};
 
-    use super::Plane;
+    use crate::day18::{Cube, Day18, SurfaceArea};
+
+    use super::{Droplet, Plane};
+
+    fn example() -> Vec<Cube> {
+        let input = fs::read_to_string("./inputs/day18_example.txt").unwrap();
+        Day18::parse(&input).unwrap()
+    }
 
     #[test]
-    fn test_union_find_shapes() -> Result<()> {
+    fn test_union_find_shapes() {
         let mut plane = Plane::new(vec![Cube(1, 1, 1), Cube(2, 1, 1), Cube(3, 2, 1)]);
 
-        plane.union(Cube(1, 1, 1), Cube(2, 1, 1))?;
+        plane.union(Cube(1, 1, 1), Cube(2, 1, 1));
 
-        let shapes = plane.get_shapes()?;
+        let shapes = plane.get_shapes();
 
         assert_eq!(shapes.len(), 2);
         assert_eq!(shapes.get(&Cube(1, 1, 1)).unwrap().len(), 2);
         assert_eq!(shapes.get(&Cube(3, 2, 1)).unwrap().len(), 1);
 
-        plane.union(Cube(2, 1, 1), Cube(3, 2, 1))?;
+        plane.union(Cube(2, 1, 1), Cube(3, 2, 1));
 
-        let shapes = plane.get_shapes()?;
+        let shapes = plane.get_shapes();
         assert_eq!(shapes.len(), 1);
         assert_eq!(shapes.get(&Cube(1, 1, 1)).unwrap().len(), 3);
-
-        Ok(())
     }
 
     #[test]
-    fn test_link_cubes() -> Result<()> {
+    fn test_link_cubes() {
         let mut plane = Plane::new(vec![Cube(1, 1, 1), Cube(2, 1, 1), Cube(3, 2, 1)]);
 
-        plane.link_connected_cubes()?;
+        plane.link_connected_cubes();
 
-        let shapes = plane.get_shapes()?;
+        let shapes = plane.get_shapes();
 
         assert_eq!(shapes.len(), 2);
         assert_eq!(shapes.get(&Cube(1, 1, 1)).unwrap().len(), 2);
         assert_eq!(shapes.get(&Cube(3, 2, 1)).unwrap().len(), 1);
-
-        Ok(())
     }
 
     #[test]
-    fn test_surface_area() -> Result<()> {
+    fn test_surface_area() {
         let mut plane = Plane::new(vec![Cube(1, 1, 1), Cube(2, 1, 1), Cube(3, 2, 1)]);
 
-        plane.link_connected_cubes()?;
+        plane.link_connected_cubes();
 
-        let shapes = plane.get_shapes()?;
+        let shapes = plane.get_shapes();
 
         assert_eq!(
             shapes.get(&Cube(1, 1, 1)).unwrap().calculate_surface_area(),
             10
         );
+    }
+
+    #[test]
+    fn test_exterior_surface_area() {
+        let cubes = example();
+
+        assert_eq!(Day18::part1(&cubes).unwrap(), 64);
+
+        let exterior = Day18::part2(&cubes).unwrap();
+        assert_eq!(exterior.area, 58);
+        assert_eq!(exterior.pockets, vec![vec![Cube(2, 2, 5)]]);
+
+        let answer = Answer::from(exterior);
+        assert_eq!(answer.to_string(), "58");
+        assert_eq!(
+            answer.note(),
+            Some("air pockets trapped inside: 1, cubes of air in them: 1")
+        );
+    }
+
+    #[test]
+    fn test_air_pockets() {
+        let cubes = example();
+        let droplet = Droplet::new(&cubes);
+
+        let pockets = droplet.air_pockets();
+        assert_eq!(pockets.len(), 1);
+        assert_eq!(pockets.get(&Cube(2, 2, 5)), Some(&vec![Cube(2, 2, 5)]));
+
+        // Pocket walls are exactly the faces the exterior area leaves out
+        let pocket_area: usize = pockets.values().map(|p| p.calculate_surface_area()).sum();
+        assert_eq!(
+            Day18::part1(&cubes).unwrap() - pocket_area,
+            droplet.exterior_surface_area()
+        );
+    }
+
+    #[test]
+    fn test_hollow_cube() {
+        // A 3x3x3 shell with a single cube of air in its middle
+        let cubes: Vec<Cube> = (0..3)
+            .flat_map(|x| (0..3).flat_map(move |y| (0..3).map(move |z| Cube(x, y, z))))
+            .filter(|c| *c != Cube(1, 1, 1))
+            .collect();
+        let droplet = Droplet::new(&cubes);
 
-        Ok(())
+        assert_eq!(droplet.exterior_surface_area(), 54);
+        assert_eq!(droplet.air_pockets().len(), 1);
     }
 }
@@ -1,14 +1,39 @@
-use std::fs;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
 use Hand::*;
 use RoundResult::*;
 
-pub fn exec() -> anyhow::Result<()> {
-    let input = fs::read_to_string("./inputs/day2.txt")?;
+pub struct Day2;
 
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
+impl Solution for Day2 {
+    type Input = Guide;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let rounds = parse::lines(input, Round::parse).map_err(Errors::from)?;
+        let rigged = parse::lines(input, RiggedRound::parse).map_err(Errors::from)?;
+
+        Ok(Guide { rounds, rigged })
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        solve_part_2(input)
+    }
+}
+
+/// The strategy guide read both ways: as the hand to play, and as how the
+/// round has to end.
+pub struct Guide {
+    rounds: Vec<Round>,
+    rigged: Vec<RiggedRound>,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -18,20 +43,16 @@ enum Hand {
     Scissors,
 }
 
-impl TryFrom<u8> for Hand {
-    type Error = ();
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            b'A' | b'X' => Ok(Self::Rock),
-            b'B' | b'Y' => Ok(Self::Paper),
-            b'C' | b'Z' => Ok(Self::Scissors),
-            _ => Err(()),
+impl Hand {
+    /// `A`, `B` and `C` for the opponent, or `X`, `Y` and `Z` for us.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        match input.one_of(&["A", "X", "B", "Y", "C", "Z"])? {
+            "A" | "X" => Ok(Self::Rock),
+            "B" | "Y" => Ok(Self::Paper),
+            _ => Ok(Self::Scissors),
         }
     }
-}
 
-impl Hand {
     fn score(&self) -> u32 {
         match &self {
             Rock => 1,
@@ -54,35 +75,31 @@ struct Round {
     right: Hand,
 }
 
-impl TryFrom<&[u8]> for Round {
-    type Error = ();
+impl TryFrom<&str> for Round {
+    type Error = Errors;
 
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        if value.len() != 3 {
-            return Err(());
-        }
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(parse::all(value, Round::parse)?)
+    }
+}
 
-        let left = Hand::try_from(value[0])?;
-        let right = Hand::try_from(value[2])?;
+impl Round {
+    /// Both hands, like `A Y`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let left = Hand::parse(input)?;
+        input.tag(" ")?;
+        let right = Hand::parse(input)?;
 
         Ok(Self { left, right })
     }
-}
 
-impl Round {
     fn score(&self) -> u32 {
         self.right.fight(&self.left) + self.right.score()
     }
 }
 
-fn solve_part_1(input: &str) -> anyhow::Result<()> {
-    let rounds: u32 = input
-        .split('\n')
-        .map(|c| Round::try_from(c.as_bytes()).unwrap().score())
-        .sum();
-
-    println!("Day 2-1: {:?}", rounds);
-    Ok(())
+fn solve_part_1(guide: &Guide) -> anyhow::Result<u32> {
+    Ok(guide.rounds.iter().map(Round::score).sum())
 }
 
 enum RoundResult {
@@ -91,20 +108,16 @@ enum RoundResult {
     Draw,
 }
 
-impl TryFrom<u8> for RoundResult {
-    type Error = ();
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
-            b'X' => Ok(Lose),
-            b'Y' => Ok(Draw),
-            b'Z' => Ok(Win),
-            _ => Err(()),
+impl RoundResult {
+    /// `X` to lose, `Y` to draw and `Z` to win.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        match input.one_of(&["X", "Y", "Z"])? {
+            "X" => Ok(Lose),
+            "Y" => Ok(Draw),
+            _ => Ok(Win),
         }
     }
-}
 
-impl RoundResult {
     fn rig(&self, hand: &Hand) -> Hand {
         match (self, hand) {
             (Win, Scissors) => Rock,
@@ -113,7 +126,7 @@ impl RoundResult {
             (Lose, Scissors) => Paper,
             (Lose, Paper) => Rock,
             (Lose, Rock) => Scissors,
-            _ => hand.clone(),
+            _ => *hand,
         }
     }
 }
@@ -123,22 +136,24 @@ struct RiggedRound {
     right: RoundResult,
 }
 
-impl TryFrom<&[u8]> for RiggedRound {
-    type Error = ();
+impl TryFrom<&str> for RiggedRound {
+    type Error = Errors;
 
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        if value.len() != 3 {
-            return Err(());
-        }
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(parse::all(value, RiggedRound::parse)?)
+    }
+}
 
-        let left = Hand::try_from(value[0])?;
-        let right = RoundResult::try_from(value[2])?;
+impl RiggedRound {
+    /// The opponent's hand and how the round must end, like `A Y`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let left = Hand::parse(input)?;
+        input.tag(" ")?;
+        let right = RoundResult::parse(input)?;
 
         Ok(Self { left, right })
     }
-}
 
-impl RiggedRound {
     fn play(&self) -> u32 {
         let right = self.right.rig(&self.left);
 
@@ -146,12 +161,6 @@ impl RiggedRound {
     }
 }
 
-fn solve_part_2(input: &str) -> anyhow::Result<()> {
-    let rounds: u32 = input
-        .split('\n')
-        .map(|c| RiggedRound::try_from(c.as_bytes()).unwrap().play())
-        .sum();
-
-    println!("Day 2-2: {:?}", rounds);
-    Ok(())
+fn solve_part_2(guide: &Guide) -> anyhow::Result<u32> {
+    Ok(guide.rigged.iter().map(RiggedRound::play).sum())
 }
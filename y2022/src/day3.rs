@@ -1,89 +1,163 @@
-use std::{collections::HashSet, fs};
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
 
-pub fn exec() -> anyhow::Result<()> {
-    let input = fs::read_to_string("./inputs/day3.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Rucksack>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse::lines(input, Rucksack::parse).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-fn solve_part_1(input: &str) -> anyhow::Result<()> {
-    let answer: u64 = input
-        .split('\n')
-        .map(&str::as_bytes)
-        .map(common_in_each_half)
-        .map(convert_to_points)
-        .sum();
-    println!("Day 3-1: {:?}", answer);
-    Ok(())
+/// The items in a rucksack, split evenly between its two compartments.
+pub struct Rucksack {
+    items: Vec<u8>,
 }
 
-fn convert_to_points(input: u8) -> u64 {
-    if input >= 65 && input <= 90 {
-        return (input - 38) as u64;
+impl Rucksack {
+    /// A line of item letters like `vJrwpWtwJgWrhcsFMMfFFhFp`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let start = *input;
+        let items = input.take_while1("an item", |c| c.is_ascii_alphabetic())?;
+
+        if !items.len().is_multiple_of(2) {
+            let message = format!("{} items do not split into two compartments", items.len());
+            return Err(start.error(message));
+        }
+
+        Ok(Rucksack {
+            items: items.as_bytes().to_vec(),
+        })
     }
 
-    return (input - 96) as u64;
+    fn compartments(&self) -> (&[u8], &[u8]) {
+        self.items.split_at(self.items.len() / 2)
+    }
 }
 
-fn common_in_each_half(input: &[u8]) -> u8 {
-    let right_start = input.len() / 2;
+/// An item found in every one of `groups`.
+fn shared(groups: &[&[u8]]) -> Option<u8> {
+    let (first, rest) = groups.split_first()?;
 
-    common(&input[0..right_start], &input[right_start..])
+    first
+        .iter()
+        .copied()
+        .find(|item| rest.iter().all(|group| group.contains(item)))
 }
 
-fn common(left: &[u8], right: &[u8]) -> u8 {
-    let mut seen = HashSet::new();
-    for c in left.iter() {
-        seen.insert(*c);
+/// `a` to `z` are worth 1 to 26 and `A` to `Z` 27 to 52.
+fn priority(item: u8) -> u64 {
+    if item.is_ascii_lowercase() {
+        (item - b'a' + 1) as u64
+    } else {
+        (item - b'A' + 27) as u64
     }
+}
 
-    for c in right.iter() {
-        if seen.contains(c) {
-            return *c;
-        }
+fn solve_part_1(rucksacks: &[Rucksack]) -> anyhow::Result<u64> {
+    let mut answer = 0;
+    for (i, rucksack) in rucksacks.iter().enumerate() {
+        let (left, right) = rucksack.compartments();
+        let item = shared(&[left, right]).ok_or_else(|| {
+            Errors::from(ParseError::at_line(
+                i + 1,
+                "no item is in both compartments",
+            ))
+        })?;
+
+        answer += priority(item);
     }
 
-    return 0;
+    Ok(answer)
 }
 
-fn commons(left: &[u8], right: &[u8]) -> HashSet<u8> {
-    let mut seen = HashSet::new();
-    for c in left.iter() {
-        seen.insert(*c);
-    }
-
-    let mut common = HashSet::new();
-    for c in right.iter() {
-        if seen.contains(c) {
-            common.insert(*c);
+fn solve_part_2(rucksacks: &[Rucksack]) -> anyhow::Result<u64> {
+    let mut answer = 0;
+    for (i, group) in rucksacks.chunks(3).enumerate() {
+        let line = i * 3 + 1;
+        if group.len() < 3 {
+            let message = format!("a group of {} rucksacks, not 3", group.len());
+            return Err(Errors::from(ParseError::at_line(line, message)).into());
         }
+
+        let items: Vec<&[u8]> = group.iter().map(|r| r.items.as_slice()).collect();
+        let badge = shared(&items).ok_or_else(|| {
+            Errors::from(ParseError::at_line(line, "no item is shared by the group"))
+        })?;
+
+        answer += priority(badge);
     }
 
-    common
+    Ok(answer)
 }
 
-fn common_chunk(c: &[&str]) -> u8 {
-    let left = commons(c[0].as_bytes(), c[1].as_bytes());
-    let right = commons(c[1].as_bytes(), c[2].as_bytes());
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for c in right.iter() {
-        if left.contains(c) {
-            return *c;
-        }
+    fn part_1(input: &str) -> anyhow::Result<u64> {
+        solve_part_1(&Day3::parse(input)?)
     }
 
-    0
-}
+    fn part_2(input: &str) -> anyhow::Result<u64> {
+        solve_part_2(&Day3::parse(input)?)
+    }
 
-fn solve_part_2(input: &str) -> anyhow::Result<()> {
-    let answer = input.split('\n').collect::<Vec<&str>>();
-    let answer: u64 = answer
-        .chunks_exact(3)
-        .map(common_chunk)
-        .map(convert_to_points)
-        .sum();
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                           jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                           PmmdzqPrVvPwwTWBwg\n\
+                           wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+                           ttgJtRGJQctTZtZT\n\
+                           CrZsJsPPZsGzwwsLwLmpwMDw\n";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part_1(EXAMPLE).unwrap(), 157);
+        assert_eq!(part_2(EXAMPLE).unwrap(), 70);
+        assert_eq!(part_1("vJrwpWtwJgWrhcsFMMfFFhFp\n").unwrap(), 16);
+    }
 
-    println!("Day 3-2: {:?}", answer);
-    Ok(())
+    #[test]
+    fn test_bad_rucksacks() {
+        let errors = [
+            (
+                part_1("abcab"),
+                "Parsing Error: `line 1, column 1: 5 items do not split into two compartments`",
+            ),
+            (
+                part_1("ab\nab1b"),
+                "Parsing Error: `line 2, column 3: expected the end of the input`",
+            ),
+            (
+                part_1("abcd"),
+                "Parsing Error: `line 1: no item is in both compartments`",
+            ),
+            (
+                part_2("ab\nab\nab\ncd\ncd\n"),
+                "Parsing Error: `line 4: a group of 2 rucksacks, not 3`",
+            ),
+            (
+                part_2("ab\ncd\nef"),
+                "Parsing Error: `line 1: no item is shared by the group`",
+            ),
+        ];
+
+        for (result, message) in errors {
+            assert_eq!(result.unwrap_err().to_string(), message);
+        }
+    }
 }
@@ -1,37 +1,60 @@
-use std::fs;
-
 use anyhow::{Ok, Result};
 
-pub fn exec() -> anyhow::Result<()> {
-    let input = fs::read_to_string("./inputs/day4.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<(Assignment, Assignment)>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse::lines(input, parse_pair).map_err(Errors::from)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
 
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-struct Assignment {
+#[derive(Debug)]
+pub struct Assignment {
     lower: u64,
     upper: u64,
 }
 
 impl TryFrom<&str> for Assignment {
-    type Error = ();
+    type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split = value.split('-').collect::<Vec<&str>>();
-        if split.len() != 2 {
-            return Err(());
-        }
-
-        let lower: u64 = split[0].parse::<u64>().unwrap();
-        let upper: u64 = split[1].parse::<u64>().unwrap();
-
-        Result::Ok(Self { lower, upper })
+        Result::Ok(parse::all(value, Assignment::parse)?)
     }
 }
 
 impl Assignment {
+    /// A section range like `2-4`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let start = *input;
+        let lower = input.unsigned()?;
+        input.tag("-")?;
+        let upper = input.unsigned()?;
+
+        if lower > upper {
+            let message = format!("range `{}-{}` runs backwards", lower, upper);
+            return Err(start.error(message));
+        }
+
+        Result::Ok(Assignment { lower, upper })
+    }
+
     fn is_inside(&self, other: &Assignment) -> bool {
         self.lower >= other.lower && self.upper <= other.upper
     }
@@ -42,34 +65,50 @@ impl Assignment {
     }
 }
 
-fn solve_part_1(input: &str) -> anyhow::Result<()> {
-    let result = input
-        .split('\n')
-        .map(|line| {
-            let split = line.split(',').collect::<Vec<&str>>();
-            (
-                Assignment::try_from(split[0]).unwrap(),
-                Assignment::try_from(split[1]).unwrap(),
-            )
-        })
+/// Two assignments like `2-4,6-8`.
+fn parse_pair(input: &mut Input) -> Result<(Assignment, Assignment), ParseError> {
+    let left = Assignment::parse(input)?;
+    input.tag(",")?;
+    let right = Assignment::parse(input)?;
+
+    Result::Ok((left, right))
+}
+
+fn solve_part_1(pairs: &[(Assignment, Assignment)]) -> anyhow::Result<usize> {
+    let result = pairs
+        .iter()
         .filter(|ass| ass.0.is_inside(&ass.1) || ass.1.is_inside(&ass.0))
         .count();
-    println!("Day 4-1: {:?}", result);
-    Ok(())
+    Ok(result)
 }
 
-fn solve_part_2(input: &str) -> anyhow::Result<()> {
-    let result = input
-        .split('\n')
-        .map(|line| {
-            let split = line.split(',').collect::<Vec<&str>>();
-            (
-                Assignment::try_from(split[0]).unwrap(),
-                Assignment::try_from(split[1]).unwrap(),
-            )
-        })
+fn solve_part_2(pairs: &[(Assignment, Assignment)]) -> anyhow::Result<usize> {
+    let result = pairs
+        .iter()
         .filter(|ass| ass.0.is_overlap(&ass.1) || ass.1.is_overlap(&ass.0))
         .count();
-    println!("Day 4-2: {:?}", result);
-    Ok(())
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n";
+
+    #[test]
+    fn test_example() {
+        let pairs = Day4::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part_1(&pairs).unwrap(), 2);
+        assert_eq!(solve_part_2(&pairs).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_backwards_range() {
+        let err = Day4::parse("2-4,6-8\n2-4,5-2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2, column 5: range `5-2` runs backwards`"
+        );
+    }
 }
@@ -1,90 +1,124 @@
-use std::fs;
-
 use anyhow::Result;
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day5.txt")?;
+use aoc_core::{
+    parse::{self, Input, ParseError},
+    Errors, Solution,
+};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Puzzle;
+    type Part1 = String;
+    type Part2 = String;
 
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Puzzle::try_from(input)?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
 
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-#[derive(Debug)]
-struct Puzzle {
+#[derive(Debug, Clone)]
+pub struct Puzzle {
     stacks: Vec<Vec<char>>,
     instructions: Vec<Instruction>,
 }
 
 impl TryFrom<&str> for Puzzle {
-    type Error = ();
+    type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<&str> = value.split("\n\n").collect();
+        Ok(parse::all(value, Puzzle::parse)?)
+    }
+}
 
-        if split.len() != 2 {
-            return Err(());
+impl Puzzle {
+    /// The drawing of the stacks, a blank line, then one move per line.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        let mut rows = vec![];
+        while let Some(row) = input.attempt(|i| i.line(|i| i.separated(" ", Puzzle::parse_crate))) {
+            rows.push(row);
         }
 
-        let stacks: Vec<Vec<char>> = Puzzle::parse_stacks(split[0]);
+        let labels = input.line(|i| {
+            i.tag(" ")?;
+            let labels = i.separated("   ", |i| i.unsigned::<usize>())?;
+            i.spaces();
+
+            Ok(labels)
+        })?;
+        input.line_end()?;
 
-        let instructions: Vec<Instruction> = split[1]
-            .split('\n')
-            .filter_map(|s| Instruction::try_from(s).ok())
-            .collect();
+        let mut stacks = vec![vec![]; labels.len()];
+        for row in rows.iter().rev() {
+            if row.len() > stacks.len() {
+                return Err(input.error("more crates than stacks in the drawing"));
+            }
+
+            for (stack, cell) in stacks.iter_mut().zip(row) {
+                stack.extend(cell);
+            }
+        }
+
+        let mut instructions = vec![];
+        while !input.is_empty() {
+            let (line, _) = input.position();
+            let instruction = input.line(Instruction::parse)?;
+
+            for label in [instruction.from, instruction.to] {
+                if label == 0 || label > stacks.len() {
+                    let message = format!("no stack {} in the drawing", label);
+                    return Err(ParseError::at_line(line, message));
+                }
+            }
+
+            instructions.push(instruction);
+        }
 
         Ok(Puzzle {
             stacks,
             instructions,
         })
     }
-}
-
-impl Puzzle {
-    fn parse_stacks(input: &str) -> Vec<Vec<char>> {
-        let input: Vec<&str> = input.split('\n').collect();
-        let mut results: Vec<Vec<char>> = vec![];
 
-        let mut idx = 1;
-
-        while idx <= input.last().unwrap().len() {
-            let mut stack = vec![];
-            for n in (0..(input.len() - 1)).rev() {
-                if input[n].as_bytes()[idx] == b' ' {
-                    continue;
-                }
-                stack.push(input[n].as_bytes()[idx] as char);
-            }
-            results.push(stack);
-            idx = idx + 4;
+    /// A crate like `[A]`, or three spaces where there is none.
+    fn parse_crate(input: &mut Input) -> Result<Option<char>, ParseError> {
+        if input.attempt(|i| i.tag("   ")).is_some() {
+            return Ok(None);
         }
 
-        results
+        input.tag("[")?;
+        let name = input.take_while1("a crate", |c| c.is_ascii_uppercase())?;
+        input.tag("]")?;
+
+        Ok(name.chars().next())
     }
 
-    fn play(&mut self) {
+    /// Moves crates one at a time, so each move reverses them.
+    fn play(&mut self) -> Result<(), Errors> {
         for instruction in &self.instructions {
-            for _ in 0..instruction.stack {
-                if let Some(item) = self.stacks[instruction.from - 1].pop() {
-                    self.stacks[instruction.to - 1].push(item);
-                }
-            }
+            let items = instruction.lift(&mut self.stacks)?;
+            self.stacks[instruction.to - 1].extend(items.iter().rev());
         }
+
+        Ok(())
     }
 
-    fn play_weird(&mut self) {
+    /// Moves crates all at once, keeping their order.
+    fn play_weird(&mut self) -> Result<(), Errors> {
         for instruction in &self.instructions {
-            let items: Vec<char> = (0..instruction.stack)
-                .into_iter()
-                .filter_map(|_| self.stacks[instruction.from - 1].pop())
-                .collect();
-
-            items
-                .iter()
-                .rev()
-                .for_each(|c| self.stacks[instruction.to - 1].push(*c));
+            let items = instruction.lift(&mut self.stacks)?;
+            self.stacks[instruction.to - 1].extend(items);
         }
+
+        Ok(())
     }
 
     fn top(&self) -> String {
@@ -92,7 +126,7 @@ impl Puzzle {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Instruction {
     stack: usize,
     from: usize,
@@ -100,33 +134,99 @@ struct Instruction {
 }
 
 impl TryFrom<&str> for Instruction {
-    type Error = ();
+    type Error = Errors;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let split: Vec<usize> = value.split(' ').filter_map(|s| s.parse().ok()).collect();
+        Ok(parse::all(value, Instruction::parse)?)
+    }
+}
 
-        if split.len() != 3 {
-            return Err(());
+impl Instruction {
+    /// A move like `move 3 from 9 to 6`.
+    fn parse(input: &mut Input) -> Result<Self, ParseError> {
+        input.tag("move ")?;
+        let stack = input.unsigned()?;
+        input.tag(" from ")?;
+        let from = input.unsigned()?;
+        input.tag(" to ")?;
+        let to = input.unsigned()?;
+
+        Ok(Instruction { stack, from, to })
+    }
+
+    /// Takes the crates this move picks up off the top of its stack, bottom
+    /// one first.
+    fn lift(&self, stacks: &mut [Vec<char>]) -> Result<Vec<char>, Errors> {
+        let from = &mut stacks[self.from - 1];
+        if self.stack > from.len() {
+            return Err(Errors::InvalidState(format!(
+                "moving {} crates from stack {}, which holds {}",
+                self.stack,
+                self.from,
+                from.len()
+            )));
         }
 
-        Ok(Instruction {
-            stack: split[0],
-            from: split[1],
-            to: split[2],
-        })
+        Ok(from.split_off(from.len() - self.stack))
     }
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let mut puzzle = Puzzle::try_from(input).unwrap();
-    puzzle.play();
-    println!("Day 5-1: {:?}", puzzle.top());
-    Ok(())
+fn solve_part_1(puzzle: &Puzzle) -> Result<String> {
+    let mut puzzle = puzzle.clone();
+    puzzle.play()?;
+    Ok(puzzle.top())
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let mut puzzle = Puzzle::try_from(input).unwrap();
-    puzzle.play_weird();
-    println!("Day 5-2: {:?}", puzzle.top());
-    Ok(())
+fn solve_part_2(puzzle: &Puzzle) -> Result<String> {
+    let mut puzzle = puzzle.clone();
+    puzzle.play_weird()?;
+    Ok(puzzle.top())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "    [D]    \n\
+                           [N] [C]    \n\
+                           [Z] [M] [P]\n \
+                           1   2   3 \n\
+                           \n\
+                           move 1 from 2 to 1\n\
+                           move 3 from 1 to 3\n\
+                           move 2 from 2 to 1\n\
+                           move 1 from 1 to 2\n";
+
+    #[test]
+    fn test_example() {
+        let puzzle = Day5::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part_1(&puzzle).unwrap(), "CMZ");
+        assert_eq!(solve_part_2(&puzzle).unwrap(), "MCD");
+    }
+
+    #[test]
+    fn test_bad_moves() {
+        let drawing = "[A] [B]\n 1   2 \n\n";
+
+        let err = Day5::parse(&format!(
+            "{}move 1 from 1 to 2\nmove 1 from 0 to 1",
+            drawing
+        ));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Parsing Error: `line 5: no stack 0 in the drawing`"
+        );
+
+        let err = Day5::parse(&format!("{}move 1 from 7 to 1", drawing));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "Parsing Error: `line 4: no stack 7 in the drawing`"
+        );
+
+        let puzzle = Day5::parse(&format!("{}move 2 from 1 to 2", drawing)).unwrap();
+        assert_eq!(
+            solve_part_2(&puzzle).unwrap_err().to_string(),
+            "Invalid State: `moving 2 crates from stack 1, which holds 1`"
+        );
+    }
 }
@@ -1,118 +1,158 @@
 use std::{
-    collections::{HashMap, HashSet},
-    fs,
-    time::Instant,
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    io::BufRead,
 };
-use thiserror::Error;
 
 use anyhow::Result;
 
-#[derive(Error, Debug)]
-enum Errors {
-    #[error("the input is not valid")]
-    InvalidInput,
-}
-
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day6.txt")?;
-
-    solve_part_1(&input, Instant::now())?;
-    solve_part_1_2(&input, Instant::now())?;
-    solve_part_2(&input, Instant::now())?;
-    solve_part_2_2(&input, Instant::now())?;
+use aoc_core::{stream, Errors, Solution, StreamingSolution};
 
-    Ok(())
-}
+pub struct Day6;
 
-fn solve_part_1(input: &str, start: Instant) -> Result<()> {
-    let results = input.as_bytes();
+impl Solution for Day6 {
+    type Input = Vec<u8>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    if results.len() <= 3 {
-        return Err(Errors::InvalidInput.into());
+    /// The datastream buffer, without the line break after it.
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim_end().as_bytes().to_vec())
     }
 
-    let results = results
-        .windows(4)
-        .position(|letters| HashSet::<u8>::from_iter(letters.iter().map(|c| *c)).len() == 4)
-        .unwrap();
-    println!(
-        "Day 6-1: {} ({:?})",
-        results + 4,
-        start.elapsed().as_micros()
-    );
-    Ok(())
-}
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve(input.iter().copied(), 4)
+    }
 
-fn solve_part_1_2(input: &str, start: Instant) -> Result<()> {
-    let higher_bound = hashmap_solve(input, 4);
-    println!(
-        "Day 6-1 (2): {} ({:?})",
-        higher_bound,
-        start.elapsed().as_micros()
-    );
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve(input.iter().copied(), 14)
+    }
 }
 
-fn solve_part_2(input: &str, start: Instant) -> Result<()> {
-    let results = input.as_bytes();
-
-    if results.len() <= 13 {
-        return Err(Errors::InvalidInput.into());
+impl StreamingSolution for Day6 {
+    fn part1_stream(input: &mut dyn BufRead) -> Result<Self::Part1> {
+        stream::process(stream::line_bytes(input), |bytes| solve(bytes, 4))?
     }
 
-    let results = results
-        .windows(14)
-        .position(|letters| HashSet::<u8>::from_iter(letters.iter().map(|c| *c)).len() == 14)
-        .unwrap();
-    println!(
-        "Day 6-2: {} ({:?})",
-        results + 14,
-        start.elapsed().as_micros()
-    );
-    Ok(())
+    fn part2_stream(input: &mut dyn BufRead) -> Result<Self::Part2> {
+        stream::process(stream::line_bytes(input), |bytes| solve(bytes, 14))?
+    }
 }
 
-fn solve_part_2_2(input: &str, start: Instant) -> Result<()> {
-    let higher_bound = hashmap_solve(input, 14);
-    println!(
-        "Day 6-2 (2): {} ({:?})",
-        higher_bound,
-        start.elapsed().as_micros()
-    );
-    Ok(())
+fn solve(input: impl Iterator<Item = u8>, cap: usize) -> Result<usize> {
+    let mut read = 0;
+    let marker = hashmap_solve(input.inspect(|_| read += 1), cap);
+
+    match marker {
+        Some(end) => Ok(end),
+        None if read < cap => Err(Errors::MissingInput(format!(
+            "a marker needs {} characters, the buffer has {}",
+            cap, read
+        ))
+        .into()),
+        None => Err(Errors::InvalidState(format!(
+            "no {} characters in a row are all different",
+            cap
+        ))
+        .into()),
+    }
 }
 
-fn hashmap_solve(input: &str, cap: usize) -> usize {
-    let input = input.as_bytes();
-
+/// Where the first `cap` characters in a row that all differ end. Only the
+/// last `cap` characters are kept while looking.
+fn hashmap_solve(input: impl IntoIterator<Item = u8>, cap: usize) -> Option<usize> {
+    let mut window = VecDeque::with_capacity(cap + 1);
     let mut seen = HashMap::<u8, usize>::new();
 
-    let mut lower_bound = 0;
-    let mut higher_bound = 0;
+    for (i, byte) in input.into_iter().enumerate() {
+        window.push_back(byte);
+        *seen.entry(byte).or_insert(0) += 1;
 
-    while higher_bound < input.len() {
-        seen.entry(input[higher_bound])
-            .and_modify(|c| *c += 1)
-            .or_insert(1);
+        if window.len() > cap {
+            if let Some(Entry::Occupied(mut count)) = window.pop_front().map(|old| seen.entry(old))
+            {
+                *count.get_mut() -= 1;
 
-        if seen.len() == cap {
-            return higher_bound + 1;
-        }
-
-        if higher_bound == lower_bound + cap - 1 {
-            seen.entry(input[lower_bound])
-                .and_modify(|c| *c -= 1)
-                .or_insert(0);
-
-            if seen.get(&input[lower_bound]).unwrap() == &0 {
-                seen.remove(&input[lower_bound]);
+                if *count.get() == 0 {
+                    count.remove();
+                }
             }
+        }
 
-            lower_bound += 1;
+        if seen.len() == cap {
+            return Some(i + 1);
         }
+    }
 
-        higher_bound += 1;
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_core::StreamingSolution;
+
+    use super::{hashmap_solve, Day6};
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(
+            hashmap_solve("mjqjpqmgbljsphdztnvjfqwrcgsmlb".bytes(), 4),
+            Some(7)
+        );
+        assert_eq!(
+            hashmap_solve("bvwbjplbgvbhsrlpgdmjqwftvncz".bytes(), 4),
+            Some(5)
+        );
+        assert_eq!(
+            hashmap_solve("nppdvjthqldpwncqszvftbjmjcrtnz".bytes(), 4),
+            Some(6)
+        );
+        assert_eq!(
+            hashmap_solve("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".bytes(), 4),
+            Some(10)
+        );
+        assert_eq!(
+            hashmap_solve("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".bytes(), 4),
+            Some(11)
+        );
+
+        assert_eq!(
+            hashmap_solve("mjqjpqmgbljsphdztnvjfqwrcgsmlb".bytes(), 14),
+            Some(19)
+        );
+        assert_eq!(
+            hashmap_solve("bvwbjplbgvbhsrlpgdmjqwftvncz".bytes(), 14),
+            Some(23)
+        );
+        assert_eq!(
+            hashmap_solve("nppdvjthqldpwncqszvftbjmjcrtnz".bytes(), 14),
+            Some(23)
+        );
+        assert_eq!(
+            hashmap_solve("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".bytes(), 14),
+            Some(29)
+        );
+        assert_eq!(
+            hashmap_solve("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".bytes(), 14),
+            Some(26)
+        );
     }
 
-    higher_bound + 1
+    #[test]
+    fn test_streaming() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n";
+        assert_eq!(Day6::part1_stream(&mut input.as_bytes()).unwrap(), 7);
+        assert_eq!(Day6::part2_stream(&mut input.as_bytes()).unwrap(), 19);
+
+        let err = Day6::part1_stream(&mut "abab\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid State: `no 4 characters in a row are all different`"
+        );
+
+        let err = Day6::part2_stream(&mut "abc".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing Input: `a marker needs 14 characters, the buffer has 3`"
+        );
+    }
 }
@@ -1,46 +1,61 @@
 use std::{
     cmp::{max, min},
     collections::HashMap,
-    fs,
 };
 
 use anyhow::Result;
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day7.txt")?;
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
+use aoc_core::{
+    parse::{Input, ParseError},
+    Errors, Solution,
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = FileSystem;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let (_root, nodes) = parse(input)?;
+fn solve_part_1(file_system: &FileSystem) -> Result<u64> {
+    let nodes = &file_system.nodes;
 
     let mut result = 0;
-    for (name, node) in nodes.iter() {
-        let file_sizes = node.size(&nodes);
+    for (_name, node) in nodes.iter() {
+        let file_sizes = node.size(nodes);
         if file_sizes <= 100000 {
             result += file_sizes;
         }
     }
 
-    println!("Day 7-1: {}", result);
-    Ok(())
+    Ok(result)
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let (root, nodes) = parse(input)?;
+fn solve_part_2(file_system: &FileSystem) -> Result<u64> {
+    let FileSystem { root, nodes } = file_system;
 
     const MAX_SIZE: u64 = 70000000;
     const UPDATE_SIZE: u64 = 30000000;
-    let total_size = root.size(&nodes);
-    let delete_size: u64 = (total_size + UPDATE_SIZE) - MAX_SIZE;
-    println!("{:?}", root.size(&nodes));
+    let total_size = root.size(nodes);
+    let delete_size: u64 = (total_size + UPDATE_SIZE).saturating_sub(MAX_SIZE);
 
     let mut minimum_sized_dir = u64::MAX;
     let mut biggest = 0;
-    for (name, node) in nodes.iter() {
-        let file_sizes = node.size(&nodes);
+    for (_name, node) in nodes.iter() {
+        let file_sizes = node.size(nodes);
 
         biggest = max(biggest, file_sizes);
 
@@ -53,13 +68,19 @@ fn solve_part_2(input: &str) -> Result<()> {
     // println!("Biggest: {:?}", biggest);
     // println!("Delete Size: {:?}", delete_size);
 
-    println!("Day 7-2: {}", minimum_sized_dir);
-    Ok(())
+    Ok(minimum_sized_dir)
 }
 
-fn parse(input: &str) -> Result<(Node, HashMap<String, Node>)> {
+/// Every directory by its full path, and the root directory.
+#[derive(Debug)]
+pub struct FileSystem {
+    root: Node,
+    nodes: HashMap<String, Node>,
+}
+
+fn parse(input: &str) -> Result<FileSystem> {
     let mut nodes = HashMap::<String, Node>::new();
-    let input: Vec<&str> = input.split('\n').collect();
+    let input: Vec<&str> = input.lines().collect();
 
     nodes.insert(
         "/".into(),
@@ -75,22 +96,26 @@ fn parse(input: &str) -> Result<(Node, HashMap<String, Node>)> {
         let line = input[i];
 
         if line == "$ cd .." {
-            if current_folder.len() >= 2 {
-                current_folder.pop();
-            } else {
-                println!("Trying to go lower than root!");
+            if current_folder.len() < 2 {
+                let message = "`cd ..` above the root";
+                return Err(Errors::from(ParseError::at_line(i + 1, message)).into());
             }
+
+            current_folder.pop();
         } else if line == "$ ls" {
-        } else if &line[0..5] == "$ cd " {
-            let name = &line[5..];
-            // println!("Go into '{:?}'", name);
+        } else if let Some(name) = line.strip_prefix("$ cd ") {
+            if current_folder.is_empty() && name != "/" {
+                let message = format!("`cd {}` before `cd /`", name);
+                return Err(Errors::from(ParseError::at_line(i + 1, message)).into());
+            }
+
             current_folder.push(name);
-        } else if &line[0..4] == "dir " {
+        } else if current_folder.is_empty() {
+            let message = "listing before `cd /`";
+            return Err(Errors::from(ParseError::at_line(i + 1, message)).into());
+        } else if let Some(name) = line.strip_prefix("dir ") {
             let parent_name = current_folder.join("/")[1..].to_string();
-            let name = &line[4..];
             let full_path = parent_name.clone() + "/" + name;
-            // println!("CurrentFolder {:?}", current_folder);
-            // println!("Parsing folder {:?}", full_path);
             nodes.entry(full_path.clone()).or_insert(Node::Directory {
                 items: vec![],
                 name: full_path.clone(),
@@ -103,35 +128,30 @@ fn parse(input: &str) -> Result<(Node, HashMap<String, Node>)> {
             };
 
             nodes.entry(parent_name.to_string()).and_modify(|e| {
-                // println!("Modifying {:?}", e);
                 insert_item(
                     e,
                     Node::Directory {
                         items: vec![],
-                        name: full_path.into(),
+                        name: full_path,
                     },
                 );
             });
-            // .or_insert(Node::Directory {
-            //     items: vec![],
-            //     name: parent_name.to_string(),
-            // });
-            // println!("{:?}", nodes);
         } else {
-            let split: Vec<&str> = line.split(' ').collect();
-
-            let size: u64 = split[0].parse().unwrap();
-            let filename: String = split[1].into();
-            // println!("Parsing File {:?} - {:?}", size, filename);
+            let (size, filename) = parse_file(Input::at_line(line, i + 1))?;
 
             let parent_name = current_folder.join("/")[1..].to_string();
+            let parent_name = if parent_name.is_empty() {
+                "/".to_string()
+            } else {
+                parent_name
+            };
+
             nodes.entry(parent_name).and_modify(|e| {
-                // println!("Modifying-File {:?}", e);
                 insert_item(
                     e,
                     Node::File {
-                        size: size,
-                        name: filename.into(),
+                        size,
+                        name: filename,
                     },
                 );
             });
@@ -140,9 +160,22 @@ fn parse(input: &str) -> Result<(Node, HashMap<String, Node>)> {
         i += 1;
     }
 
-    // println!("All Nodes: {:?}", nodes);
+    let root = nodes
+        .get("/")
+        .cloned()
+        .ok_or_else(|| Errors::InvalidState("no root directory".into()))?;
 
-    Ok((nodes.get("/".into()).unwrap().clone(), nodes))
+    Ok(FileSystem { root, nodes })
+}
+
+/// A file in a listing, like `14848514 b.txt`.
+fn parse_file(mut input: Input) -> Result<(u64, String), Errors> {
+    let size = input.unsigned()?;
+    input.tag(" ")?;
+    let name = input.take_while1("a file name", |c| !c.is_whitespace())?;
+    input.end()?;
+
+    Ok((size, name.to_string()))
 }
 
 fn insert_item(node: &mut Node, item: Node) {
@@ -150,7 +183,7 @@ fn insert_item(node: &mut Node, item: Node) {
         Node::Directory { items, name: _ } => {
             items.push(item);
         }
-        Node::File { size, name } => {}
+        Node::File { size: _, name: _ } => {}
     }
 }
 
@@ -163,7 +196,7 @@ enum Node {
 impl Node {
     fn size(&self, folder_mapping: &HashMap<String, Node>) -> u64 {
         match self {
-            Self::Directory { items, name } => {
+            Self::Directory { items, name: _ } => {
                 let folder_sizes: u64 = self
                     .folders()
                     .iter()
@@ -174,41 +207,33 @@ impl Node {
                 items
                     .iter()
                     .filter(|n| n.is_file())
-                    .map(|n| n.size(&folder_mapping))
+                    .map(|n| n.size(folder_mapping))
                     .sum::<u64>()
                     + folder_sizes
             }
-            Self::File { size, name } => *size,
+            Self::File { size, name: _ } => *size,
         }
     }
 
     fn name(&self) -> String {
         match self {
-            Self::Directory { items, name } => name.into(),
-            Self::File { size, name } => name.into(),
+            Self::Directory { items: _, name } => name.into(),
+            Self::File { size: _, name } => name.into(),
         }
     }
 
     fn is_directory(&self) -> bool {
-        if let Self::Directory { name: _, items: _ } = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::Directory { name: _, items: _ })
     }
 
     fn is_file(&self) -> bool {
-        if let Self::Directory { name: _, items: _ } = self {
-            false
-        } else {
-            true
-        }
+        !matches!(self, Self::Directory { name: _, items: _ })
     }
 
     fn folders(&self) -> Vec<String> {
         match self {
-            Self::File { size, name } => vec![],
-            Self::Directory { items, name } => items
+            Self::File { size: _, name: _ } => vec![],
+            Self::Directory { items, name: _ } => items
                 .iter()
                 .filter(|n| n.is_directory())
                 .map(|n| n.name())
@@ -216,3 +241,37 @@ impl Node {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc_core::Solution;
+
+    use super::{solve_part_1, solve_part_2, Day7};
+
+    const EXAMPLE: &str = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n\
+                           $ cd a\n$ ls\ndir e\n29116 f\n2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n\
+                           $ cd ..\n$ cd ..\n$ cd d\n$ ls\n4060174 j\n8033020 d.log\n5626152 d.ext\n\
+                           7214296 k\n";
+
+    #[test]
+    fn test_example() {
+        let file_system = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part_1(&file_system).unwrap(), 95437);
+        assert_eq!(solve_part_2(&file_system).unwrap(), 24933642);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Day7::parse("$ cd /\n$ cd ..\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 2: `cd ..` above the root`"
+        );
+
+        let err = Day7::parse("$ cd /\n$ ls\n12 a\nb\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parsing Error: `line 4, column 1: expected a number`"
+        );
+    }
+}
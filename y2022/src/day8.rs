@@ -1,183 +1,116 @@
-use std::{cmp::max, collections::HashSet, fs, iter::zip};
+use std::collections::HashSet;
 
-use anyhow::{Ok, Result};
+use anyhow::{anyhow, Result};
 
-pub fn exec() -> Result<()> {
-    let input = fs::read_to_string("./inputs/day8.txt")?;
+use aoc_core::{Direction, Errors, Grid, Point, Solution};
 
-    solve_part_1(&input)?;
-    solve_part_2(&input)?;
-    Ok(())
-}
+pub struct Day8;
 
-fn solve_part_1(input: &str) -> Result<()> {
-    let input: Vec<&[u8]> = input.split('\n').map(|v| v.as_bytes()).collect();
+impl Solution for Day8 {
+    type Input = Grid<u8>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let mut processed_input = vec![];
-    for x in 0..input.len() {
-        let mut cols = vec![];
-        for y in 0..input[x].len() {
-            cols.push((Coord { x, y }, input[x][y]));
-        }
-        processed_input.push(cols);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Grid::parse(input, |c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| anyhow!("tree height `{}` is not a digit", c))
+        })
     }
 
-    let results = visible_trees(&processed_input);
+    fn part1(input: &Self::Input) -> Result<Self::Part1> {
+        solve_part_1(input)
+    }
 
-    println!("Day 8-1: {}", results);
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<Self::Part2> {
+        solve_part_2(input)
+    }
 }
 
-fn solve_part_2(input: &str) -> Result<()> {
-    let input: Vec<&[u8]> = input.split('\n').map(|v| v.as_bytes()).collect();
-
-    let mut total_scores = vec![];
-    for y in 0..input.len() {
-        let mut scores = vec![];
-        for x in 0..input[y].len() {
-            let item = input[y][x];
-            let mut score = 1;
-
-            // right
-            let mut tmp_score = 0;
-            if (x + 1) <= input[y].len() {
-                for i in &input[y][(x + 1)..input[y].len()] {
-                    tmp_score += 1;
-
-                    if item <= *i {
-                        break;
-                    }
-                }
-                // println!("{:?},{:?} - {:?}", x, y, tmp_score);
-            }
-
-            score *= tmp_score;
+fn solve_part_1(grid: &Grid<u8>) -> Result<usize> {
+    let mut visible = HashSet::new();
 
-            // left
-            let mut tmp_score = 0;
+    for (y, row) in grid.rows().enumerate() {
+        visible.extend(visible_in_line(row).into_iter().map(|x| Point::new(x, y)));
+    }
 
-            if x > 0 {
-                for i in input[y][0..x].iter().rev() {
-                    // println!("{:?} vs {:?}", item, *i);
-                    tmp_score += 1;
+    // Columns are the rows of the transposed grid
+    for (x, column) in grid.transpose().rows().enumerate() {
+        visible.extend(
+            visible_in_line(column)
+                .into_iter()
+                .map(|y| Point::new(x, y)),
+        );
+    }
 
-                    if item <= *i {
-                        break;
-                    }
-                }
-            }
+    Ok(visible.len())
+}
 
-            // println!("{:?},{:?} - {:?}", x, y, tmp_score);
-            score *= tmp_score;
-            // up
-            let mut tmp_score = 0;
+fn solve_part_2(grid: &Grid<u8>) -> Result<usize> {
+    let scenic_score = |tree: Point<usize>| -> usize {
+        let height = grid[tree];
 
-            if y > 0 {
-                for i in (0..y).rev() {
-                    let t = input[i][x];
-                    tmp_score += 1;
+        Direction::ALL
+            .into_iter()
+            .map(|direction| {
+                let mut seen = 0;
+                for (_, other) in grid.ray(tree, direction) {
+                    seen += 1;
 
-                    if item <= t {
+                    if height <= *other {
                         break;
                     }
                 }
-            }
-
-            // println!("{:?},{:?} - {:?}", x, y, tmp_score);
-            score *= tmp_score;
-            // down
-            let mut tmp_score = 0;
+                seen
+            })
+            .product()
+    };
+
+    let result = grid
+        .points()
+        .map(scenic_score)
+        .max()
+        .ok_or(Errors::InvalidState("No trees".into()))?;
+
+    Ok(result)
+}
 
-            if y + 1 <= input.len() {
-                for i in (y + 1)..input.len() {
-                    let t = input[i][x];
-                    tmp_score += 1;
+/// Positions in the line of trees seen from either of its ends.
+fn visible_in_line(line: &[u8]) -> HashSet<usize> {
+    let mut visible = visible_num(line.iter().copied().enumerate());
+    visible.extend(visible_num(line.iter().copied().enumerate().rev()));
 
-                    if item <= t {
-                        break;
-                    }
-                }
-            }
+    visible
+}
 
-            // println!("{:?},{:?} - {:?}", x, y, tmp_score);
-            score *= tmp_score;
+fn visible_num(line: impl Iterator<Item = (usize, u8)>) -> HashSet<usize> {
+    let mut visible = HashSet::new();
+    let mut highest_seen = None;
 
-            scores.push(score);
+    for (i, height) in line {
+        if highest_seen.is_none_or(|highest| height > highest) {
+            visible.insert(i);
+            highest_seen = Some(height);
         }
-
-        total_scores.push(scores);
     }
-    // println!("{:?}", total_scores);
-
-    let results = total_scores
-        .iter()
-        .map(|r| r.iter().fold(0, |acc, item| -> i32 { max(acc, *item) }))
-        .fold(0, |acc, item| max(acc, item));
-
-    println!("Day 8-2: {:?}", results);
-    Ok(())
-}
 
-fn visible_trees(input: &Vec<Vec<(Coord, u8)>>) -> usize {
-    // Left to right
-    let vis_left = input.iter().map(|r| visible_num(r));
-    // Check right to left
-    let vis_right = input.iter().map(|r| {
-        let s: Vec<(Coord, u8)> = r.iter().map(|(c, v)| (*c, *v)).rev().collect();
-        visible_num(&s)
-    });
-    let mut horizontal =
-        zip(vis_left, vis_right).fold(HashSet::<Coord>::new(), |mut acc, (mut left, right)| {
-            left.extend(right);
-            acc.extend(left);
-            acc
-        });
-
-    let rotated = rotate(input);
-    // Check up and down
-    let vis_up = rotated.iter().map(|r| visible_num(r));
-    // Check down and up
-    let vis_down = rotated.iter().map(|r| {
-        let s: Vec<(Coord, u8)> = r.iter().map(|(c, v)| (*c, *v)).rev().collect();
-        visible_num(&s)
-    });
-    let vertical =
-        zip(vis_up, vis_down).fold(HashSet::<Coord>::new(), |mut acc, (mut left, right)| {
-            left.extend(right);
-            acc.extend(left);
-            acc
-        });
-
-    horizontal.extend(vertical);
-    horizontal.len()
+    visible
 }
 
-fn rotate(input: &Vec<Vec<(Coord, u8)>>) -> Vec<Vec<(Coord, u8)>> {
-    let mut results = vec![];
+#[cfg(test)]
+mod tests {
+    use aoc_core::Solution;
 
-    for i in (0..input[0].len()).into_iter() {
-        results.push(input.iter().map(|r| r[i]).collect());
-    }
+    use super::Day8;
 
-    results
-}
+    const EXAMPLE: &str = "30373\n25512\n65332\n33549\n35390";
 
-#[derive(PartialEq, Hash, Eq, Clone, Copy)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
+    #[test]
+    fn test_example() {
+        let grid = Day8::parse(EXAMPLE).unwrap();
 
-fn visible_num<'a>(input: &Vec<(Coord, u8)>) -> HashSet<Coord> {
-    let mut visible = HashSet::new();
-    let mut highest_seen = b'0' - 1;
-
-    for i in input {
-        if i.1 > highest_seen {
-            visible.insert(i.0);
-            highest_seen = i.1;
-        }
+        assert_eq!(Day8::part1(&grid).unwrap(), 21);
+        assert_eq!(Day8::part2(&grid).unwrap(), 8);
     }
-
-    visible
 }
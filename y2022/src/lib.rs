@@ -18,84 +18,33 @@ pub mod day8;
 pub mod day9;
 pub mod day_18_tests;
 
-use std::{num::ParseIntError, str::Utf8Error};
-
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-enum Errors {
-    #[error("Parsing Error: `{0}`")]
-    ParseError(String),
-    #[error("Parsing Int Error: `{0}`")]
-    ParseIntError(#[from] ParseIntError),
-    #[error("Parsing Utf8 Error: `{0}`")]
-    ParseUtf8Error(#[from] Utf8Error),
-    #[error("Invalid State: `{0}`")]
-    InvalidState(String),
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq)]
-struct Coord {
-    x: isize,
-    y: isize,
+use std::path::Path;
+
+use aoc_core::{Point, Registry};
+
+pub fn register(registry: &mut Registry) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    registry.add_input_dir(2022, dir.join("inputs"));
+    registry.add_answers_file(2022, dir.join("answers.toml"));
+
+    registry.register_streaming::<day1::Day1>(2022, 1);
+    registry.register::<day2::Day2>(2022, 2);
+    registry.register::<day3::Day3>(2022, 3);
+    registry.register::<day4::Day4>(2022, 4);
+    registry.register::<day5::Day5>(2022, 5);
+    registry.register_streaming::<day6::Day6>(2022, 6);
+    registry.register::<day7::Day7>(2022, 7);
+    registry.register::<day8::Day8>(2022, 8);
+    registry.register::<day9::Day9>(2022, 9);
+    registry.register_streaming::<day10::Day10>(2022, 10);
+    registry.register::<day11::Day11>(2022, 11);
+    registry.register::<day12::Day12>(2022, 12);
+    registry.register::<day13::Day13>(2022, 13);
+    registry.register::<day14::Day14>(2022, 14);
+    registry.register::<day15::Day15>(2022, 15);
+    registry.register::<day16::Day16>(2022, 16);
+    registry.register::<day17::Day17>(2022, 17);
+    registry.register::<day18::Day18>(2022, 18);
 }
 
-impl Coord {
-    fn new(x: isize, y: isize) -> Self {
-        Coord { x, y }
-    }
-
-    fn add(&self, other: &Coord) -> Coord {
-        Coord {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
-    }
-
-    fn adjust(&self, reference: &Coord) -> Coord {
-        Coord {
-            x: reference.x + self.x,
-            y: reference.y + self.y,
-        }
-    }
-
-    fn normalize_y_to(&self, to: isize) -> Coord {
-        Coord { x: self.x, y: to }
-    }
-
-    fn normalize_x_to(&self, to: isize) -> Coord {
-        Coord { x: to, y: self.y }
-    }
-
-    fn apply(&mut self, other: &Coord) {
-        self.x += other.x;
-        self.y += other.y;
-    }
-
-    fn is_on_same_axis(&self, other: &Coord) -> bool {
-        self.x == other.x || self.y == other.y
-    }
-
-    fn direction_towards(&self, other: &Coord) -> Coord {
-        Coord {
-            x: if self.x == other.x {
-                0
-            } else {
-                if self.x < other.x {
-                    1
-                } else {
-                    -1
-                }
-            },
-            y: if self.y == other.y {
-                0
-            } else {
-                if self.y < other.y {
-                    1
-                } else {
-                    -1
-                }
-            },
-        }
-    }
-}
+type Coord = Point<isize>;
@@ -1,28 +1,10 @@
-use y2022::{
-    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day2, day3, day4, day5,
-    day6, day7, day8, day9,
-};
+use std::process::ExitCode;
 
-fn main() -> anyhow::Result<()> {
-    println!("Advent of Code - 2022");
-    // day1::exec()?;
-    // day2::exec()?;
-    // day3::exec()?;
-    // day4::exec()?;
-    // day5::exec()?;
-    // day6::exec()?;
-    // day7::exec()?;
-    // day8::exec()?;
-    // day9::exec()?;
-    // day10::exec()?;
-    // day11::exec()?;
-    // day12::exec()?;
-    // day13::exec()?;
-    // day14::exec()?;
-    // day15::exec()?;
-    // day16::exec()?;
-    // day17::exec()?;
-    day18::exec()?;
+use aoc_core::Registry;
 
-    Ok(())
+fn main() -> ExitCode {
+    let mut registry = Registry::new();
+    y2022::register(&mut registry);
+
+    aoc_core::cli::main(registry)
 }